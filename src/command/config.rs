@@ -0,0 +1,105 @@
+use anyhow::Result;
+use prettytable::row;
+use std::path::PathBuf;
+
+use super::statistics_configuration::Configuration;
+use super::statistics_configuration_file::{
+    ConfigurationSource, ConfigurationValues, LayeredConfiguration, CONFIGURATION_KEYS,
+};
+use super::statistics_print::create_table;
+use crate::ExitCode;
+
+/// Format the effective value of a configuration key.
+fn configuration_value(configuration: &Configuration, key: &str) -> String {
+    match key {
+        "estimator" => configuration.estimator.to_string(),
+        "diff-reference-lines" => configuration.diff_weights.reference_lines.to_string(),
+        "diff-exponent" => configuration.diff_weights.exponent.to_string(),
+        "diff-max-factor" => configuration.diff_weights.max_factor.to_string(),
+        "diff-minutes-per-line" => configuration.diff_weights.minutes_per_line.to_string(),
+        "max-commit-diff" => configuration.max_commit_diff.num_minutes().to_string(),
+        "first-commit-add" => configuration
+            .first_commit_addition
+            .num_minutes()
+            .to_string(),
+        "legacy-first-session" => configuration.legacy_first_session.to_string(),
+        "since" => configuration.since.to_string(),
+        "until" => configuration.until.to_string(),
+        "merges" => configuration.merges.to_string(),
+        "first-parent" => configuration.first_parent.to_string(),
+        "recurse-submodules" => configuration.recurse_submodules.to_string(),
+        "email" => {
+            let mut aliases = configuration
+                .email_aliases
+                .iter()
+                .map(|(other, main)| format!("{}={}", other, main))
+                .collect::<Vec<_>>();
+            aliases.sort();
+            aliases.join(", ")
+        }
+        "mailmap" => configuration.mailmap.to_string(),
+        "branch" => configuration.branches.join(", "),
+        "exclude-branch" => configuration.exclude_branches.join(", "),
+        "author" => configuration.authors.join(", "),
+        "exclude-author" => configuration.exclude_authors.join(", "),
+        "bots" => configuration.bots.join(", "),
+        "include-bots" => configuration.include_bots.to_string(),
+        "tags" => configuration.tags.to_string(),
+        "revision" => configuration.revisions.join(", "),
+        "branch-type" => configuration.branch_type.to_string(),
+        "format" => configuration.output_format.to_string().to_lowercase(),
+        "totals" => configuration.totals.to_string(),
+        "sessions" => configuration.sessions.to_string(),
+        "by-repository" => configuration.by_repository.to_string(),
+        "by-component" => configuration.by_component.to_string(),
+        "component-depth" => configuration.component_depth.to_string(),
+        "components" => configuration
+            .components
+            .iter()
+            .map(|(name, patterns)| format!("{}={}", name, patterns.join("|")))
+            .collect::<Vec<_>>()
+            .join(", "),
+        "group-by" => configuration
+            .group_by
+            .map(|g| g.to_string().to_lowercase())
+            .unwrap_or_default(),
+        "time-zone" => configuration.time_zone.to_string(),
+        "json-legacy" => configuration.json_legacy.to_string(),
+        _ => String::new(),
+    }
+}
+
+/// Command to print the effective configuration and the source of each value.
+pub fn config(args: &clap::ArgMatches) -> Result<ExitCode> {
+    let args_config = args.subcommand_matches("config").unwrap();
+    let args_show = match args_config.subcommand_matches("show") {
+        Some(args_show) => args_show,
+        None => {
+            println!("{}", args_config.usage());
+            return Ok(1);
+        }
+    };
+
+    let git_repo_path = PathBuf::from(args_show.value_of("REPO_PATH").unwrap());
+    let mut layered = LayeredConfiguration::discover(&git_repo_path)?;
+    layered.layer(
+        ConfigurationValues::from_arguments(args_show)?,
+        ConfigurationSource::CommandLine,
+    );
+    let configuration = layered.build(&[git_repo_path])?;
+
+    let mut table = create_table();
+    table.set_titles(row!["Key", "Value", "Source"]);
+    table.add_empty_row();
+    for key in CONFIGURATION_KEYS.iter() {
+        table.add_row(row![
+            key,
+            configuration_value(&configuration, key),
+            layered.source(key)
+        ]);
+    }
+
+    table.printstd();
+
+    Ok(0)
+}
@@ -1,3 +1,10 @@
+pub mod config;
 pub mod statistics;
+pub mod statistics_author;
+pub mod statistics_component;
 pub mod statistics_configuration;
+pub mod statistics_configuration_file;
+pub mod statistics_estimator;
+pub mod statistics_output;
+pub mod statistics_period;
 pub mod statistics_print;
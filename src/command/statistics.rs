@@ -8,72 +8,183 @@ extern crate regex;
 extern crate serde;
 extern crate serde_json;
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context, Result};
 use chrono::{Duration, Local, TimeZone, Utc};
-use git2::{BranchType, Commit, Repository};
+use git2::{
+    Commit, Diff, DiffOptions, Mailmap, Object, Oid, Reference, Repository, RevparseMode, Revwalk,
+    Signature,
+};
 use regex::Regex;
 use std::cmp::Ordering;
-use std::collections::{HashMap, HashSet};
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::path::{Component, Path, PathBuf};
 use std::string::ToString;
 
+use crate::command::statistics_author::{partition_bots, AuthorFilter};
+use crate::command::statistics_component::{commit_components, split_duration, ComponentMatcher};
+use crate::command::statistics_estimator::{DiffWeights, Estimator, EstimatorKind};
+use crate::command::statistics_output::ReportWriter;
 use crate::command::statistics_print::print_results;
-use crate::git::CommitHours;
+use crate::git::{CommitDuration, CommitHours, CommitTimeBound, RepositoryHours, WorkSession};
 use crate::ExitCode;
 
-use super::statistics_configuration::{parse_arguments, Configuration};
+use super::statistics_configuration::{parse_arguments, BranchKind, Configuration, MergeCommits};
 
-/// Get commits of a specific repository branch.
-fn get_commits<'repo>(
+/// Add the commits of a git revision (`tag`, `a..b`, `a...b`, `a..` or `^a`) to a revwalk.
+fn push_revision(repo: &Repository, revwalk: &mut Revwalk, revision: &str) -> Result<()> {
+    let to_commit = |object: &Object| -> Result<Oid> { Ok(object.peel_to_commit()?.id()) };
+
+    if let Some(hidden) = revision.strip_prefix('^') {
+        let object = repo
+            .revparse_single(hidden)
+            .with_context(|| format!("Invalid revision '{}'.", revision))?;
+        revwalk.hide(to_commit(&object)?)?;
+        return Ok(());
+    }
+
+    let revspec = repo
+        .revparse(revision)
+        .with_context(|| format!("Invalid revision '{}'.", revision))?;
+    let from = revspec.from().map(to_commit).transpose()?;
+    // An open range like `a..` ends at `HEAD`.
+    let to = match revspec.to() {
+        Some(object) => Some(to_commit(object)?),
+        None if revspec.mode().contains(RevparseMode::RANGE) => {
+            Some(repo.head()?.peel_to_commit()?.id())
+        }
+        None => None,
+    };
+
+    match (from, to) {
+        (Some(from), Some(to)) if revspec.mode().contains(RevparseMode::MERGE_BASE) => {
+            revwalk.push(from)?;
+            revwalk.push(to)?;
+            if let Ok(base) = repo.merge_base(from, to) {
+                revwalk.hide(base)?;
+            }
+        }
+        (Some(from), Some(to)) => {
+            revwalk.hide(from)?;
+            revwalk.push(to)?;
+        }
+        (Some(oid), None) | (None, Some(oid)) => revwalk.push(oid)?,
+        (None, None) => return Err(anyhow!("Invalid revision '{}'.", revision)),
+    }
+
+    Ok(())
+}
+
+/// Get commits of the revisions selected by the configuration, like `git log` would.
+fn get_revision_commits<'repo>(
     repo: &'repo Repository,
-    branch: &Option<String>,
-    branch_kind: BranchType,
+    configuration: &Configuration,
 ) -> Result<Vec<Commit<'repo>>> {
-    let refs = repo.references()?;
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(git2::Sort::TIME | git2::Sort::REVERSE)?;
+    for revision in configuration.revisions.iter() {
+        push_revision(repo, &mut revwalk, revision)?;
+    }
+    if configuration.follows_first_parent() {
+        revwalk.simplify_first_parent()?;
+    }
 
-    let ref_prefix = match branch_kind {
-        BranchType::Local => "heads",
-        BranchType::Remote => "remotes",
-    };
+    let mut result = Vec::new();
+    for oid in revwalk {
+        result.push(repo.find_commit(oid?)?);
+    }
 
-    let branch_refs = match branch {
-        Some(b) => {
-            let s = format!("refs/{}/{}", ref_prefix, b);
-            let mut vec = Vec::new();
-            for r in refs {
-                let r = r?;
-                let name = r.name();
-                if let Some(name) = name {
-                    if name == s {
-                        vec.push(r)
-                    }
-                }
+    Ok(result)
+}
+
+/// Translate a path glob into an unanchored regex.
+///
+/// `*` and `?` match within one path segment and `**` matches across segments, so `release/*`
+/// matches `release/1.0` but not `release/1.0/hotfix`.
+pub(crate) fn glob_to_regex(pattern: &str) -> String {
+    let mut rx = String::new();
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                rx.push_str(".*");
             }
-            vec
+            '*' => rx.push_str("[^/]*"),
+            '?' => rx.push_str("[^/]"),
+            c => rx.push_str(&regex::escape(&c.to_string())),
         }
-        None => {
-            let mut vec = Vec::new();
-            let rx = Regex::new(&format!("refs/{}/.*", ref_prefix))?;
-            for r in refs {
-                let r = r?;
-                let name = r.name();
-                if let Some(name) = name {
-                    if rx.is_match(name) {
-                        vec.push(r)
-                    }
-                }
+    }
+
+    rx
+}
+
+/// Compile a branch glob into a regex matching full reference names below `ref_prefix`.
+fn branch_pattern(ref_prefix: &str, pattern: &str) -> Result<Regex> {
+    let rx = format!("^{}/{}$", regex::escape(ref_prefix), glob_to_regex(pattern));
+
+    Ok(Regex::new(&rx)?)
+}
+
+/// Get the references selected by the branches, excluded branches and tags of the configuration.
+fn get_references<'repo>(
+    repo: &'repo Repository,
+    configuration: &Configuration,
+) -> Result<Vec<Reference<'repo>>> {
+    let patterns = |branches: &[String]| -> Result<Vec<Regex>> {
+        let mut result = Vec::new();
+        for ref_prefix in configuration.branch_type.ref_prefixes() {
+            for branch in branches.iter() {
+                result.push(branch_pattern(ref_prefix, branch)?);
             }
-            vec
         }
+        Ok(result)
+    };
+
+    let included = if configuration.branches.is_empty() {
+        patterns(&[String::from("**")])?
+    } else {
+        patterns(&configuration.branches)?
     };
+    let excluded = patterns(&configuration.exclude_branches)?;
+    let tags = branch_pattern("refs/tags", "**")?;
+
+    let mut result = Vec::new();
+    for r in repo.references()? {
+        let r = r?;
+        if let Some(name) = r.name() {
+            let branch = included.iter().any(|rx| rx.is_match(name))
+                && !excluded.iter().any(|rx| rx.is_match(name));
+            let tag = configuration.tags && tags.is_match(name);
+            if branch || tag {
+                result.push(r);
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// Get commits of the revisions or branches selected by the configuration.
+pub fn get_commits<'repo>(
+    repo: &'repo Repository,
+    configuration: &Configuration,
+) -> Result<Vec<Commit<'repo>>> {
+    if !configuration.revisions.is_empty() {
+        return get_revision_commits(repo, configuration);
+    }
 
     let mut result = Vec::new();
     let mut seen = HashSet::new();
-    for r in branch_refs.iter() {
-        if let Some(latest_oid) = r.target() {
+    for r in get_references(repo, configuration)?.iter() {
+        // Symbolic references like `origin/HEAD` and tags of trees are skipped.
+        if let (Some(_), Ok(latest)) = (r.target(), r.peel_to_commit()) {
             let mut revwalk = repo.revwalk()?;
             revwalk.set_sorting(git2::Sort::TIME | git2::Sort::REVERSE)?;
-            revwalk.push(latest_oid)?;
+            revwalk.push(latest.id())?;
+            if configuration.follows_first_parent() {
+                revwalk.simplify_first_parent()?;
+            }
             for oid in revwalk {
                 let oid = oid?;
                 if !seen.contains(&oid) {
@@ -88,8 +199,8 @@ fn get_commits<'repo>(
     Ok(result)
 }
 
-// Filter out commits in a given time period.
-fn filter_commits<'repo>(
+/// Filter out commits in a given time period.
+pub fn filter_commits<'repo>(
     configuration: &Configuration,
     commits: Vec<Commit<'repo>>,
 ) -> Vec<Commit<'repo>> {
@@ -116,54 +227,254 @@ fn filter_commits<'repo>(
                 }
             }
 
-            configuration.merge_requests
-                || !commit
-                    .summary()
-                    .map(|s| s.starts_with("Merge "))
-                    .unwrap_or(false)
+            !is_excluded_merge(commit, configuration.merges)
+        })
+        .collect()
+}
+
+/// Diff of a commit against its first parent, or against the empty tree for a root commit, limited
+/// to paths matching the pathspecs.
+pub(crate) fn diff_to_first_parent<'repo>(
+    repo: &'repo Repository,
+    commit: &Commit,
+    pathspecs: &[String],
+) -> Result<Diff<'repo>> {
+    let mut options = DiffOptions::new();
+    for pathspec in pathspecs.iter() {
+        options.pathspec(pathspec);
+    }
+
+    let tree = commit.tree()?;
+    let parent_tree = match commit.parent_count() {
+        0 => None,
+        _ => Some(commit.parent(0)?.tree()?),
+    };
+
+    Ok(repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), Some(&mut options))?)
+}
+
+/// Whether the changes of a commit against its first parent touch paths matching the pathspecs.
+fn touches_pathspecs(repo: &Repository, commit: &Commit, pathspecs: &[String]) -> Result<bool> {
+    let diff = diff_to_first_parent(repo, commit, pathspecs)?;
+
+    Ok(diff.deltas().len() > 0)
+}
+
+/// Lines a commit changed against its first parent.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LineStats {
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+impl LineStats {
+    /// Lines inserted or deleted.
+    pub fn lines(&self) -> usize {
+        self.insertions + self.deletions
+    }
+}
+
+/// Lines a commit changed against its first parent, within the pathspecs of the configuration.
+pub fn commit_line_stats(
+    repo: &Repository,
+    commit: &Commit,
+    configuration: &Configuration,
+) -> Result<LineStats> {
+    let stats = diff_to_first_parent(repo, commit, &configuration.pathspecs)?.stats()?;
+
+    Ok(LineStats {
+        insertions: stats.insertions(),
+        deletions: stats.deletions(),
+    })
+}
+
+/// Keep only commits touching paths matching the pathspecs of the configuration, if any.
+pub fn filter_commits_by_pathspec<'repo>(
+    repo: &'repo Repository,
+    configuration: &Configuration,
+    commits: Vec<Commit<'repo>>,
+) -> Result<Vec<Commit<'repo>>> {
+    if configuration.pathspecs.is_empty() {
+        return Ok(commits);
+    }
+
+    let mut result = Vec::new();
+    for commit in commits {
+        if touches_pathspecs(repo, &commit, &configuration.pathspecs)? {
+            result.push(commit);
+        }
+    }
+
+    Ok(result)
+}
+
+/// Whether a commit is a merge commit that the merge mode excludes.
+fn is_excluded_merge(commit: &Commit, merges: MergeCommits) -> bool {
+    match merges {
+        MergeCommits::Exclude => commit.parent_count() > 1,
+        MergeCommits::Include | MergeCommits::FirstParent => false,
+        MergeCommits::Message => commit
+            .summary()
+            .map(|s| s.starts_with("Merge "))
+            .unwrap_or(false),
+    }
+}
+
+/// A commit together with the repository it was found in and its resolved author.
+pub struct RepositoryCommit<'repo> {
+    pub repository: String,
+    pub commit: Commit<'repo>,
+    pub author: Signature<'static>,
+    /// Lines changed by component, when components are computed.
+    pub components: Option<BTreeMap<String, usize>>,
+    /// Lines changed, when the estimator reads them.
+    pub line_stats: Option<LineStats>,
+}
+
+impl fmt::Debug for RepositoryCommit<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RepositoryCommit")
+            .field("repository", &self.repository)
+            .field("commit", &self.commit)
+            .field("author", &self.author.to_string())
+            .field("components", &self.components)
+            .field("line_stats", &self.line_stats)
+            .finish()
+    }
+}
+
+/// Tag commits with their repository and resolve their authors through the `mailmap`.
+pub fn resolve_commits<'repo>(
+    repository: &str,
+    commits: Vec<Commit<'repo>>,
+    mailmap: Option<&Mailmap>,
+) -> Result<Vec<RepositoryCommit<'repo>>> {
+    commits
+        .into_iter()
+        .map(|commit| {
+            let author = match mailmap {
+                Some(mailmap) => commit.author_with_mailmap(mailmap)?,
+                None => commit.author().to_owned(),
+            };
+            Ok(RepositoryCommit {
+                repository: repository.to_string(),
+                commit,
+                author,
+                components: None,
+                line_stats: None,
+            })
         })
         .collect()
 }
 
 // Collect time estimate by author.
 fn estimate_author_time(
-    mut commits: Vec<&Commit>,
+    estimator: &dyn Estimator,
+    mut commits: Vec<&RepositoryCommit>,
     email: Option<String>,
-    max_commit_diff: &Duration,
-    first_commit_addition: &Duration,
+    author_name: Option<String>,
+    configuration: &Configuration,
 ) -> CommitHours {
-    let author_name = commits[0].author().name().map(|n| n.to_string());
+    commits.sort_by_key(|c| c.commit.time());
 
-    commits.sort_by_key(|c| c.time());
+    let estimate = estimator.estimate(&commits, configuration);
 
-    let len = commits.len() - 1;
-    let all_but_last = commits.iter().enumerate().take(len);
-    let duration = all_but_last.fold(Duration::minutes(0), |acc, (i, commit)| {
-        let next_commit = commits.get(i + 1).unwrap();
-        let diff_seconds = next_commit.time().seconds() - commit.time().seconds();
-        let dur = Duration::seconds(diff_seconds);
+    // Time spent on a commit is split across the components it changed.
+    let mut components: BTreeMap<String, Duration> = BTreeMap::new();
+    let mut sessions = Vec::new();
+    for session in estimate.sessions.iter() {
+        let (first, last) = match (session.commits.first(), session.commits.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => continue,
+        };
 
-        if dur < *max_commit_diff {
-            acc + dur
-        } else {
-            acc + *first_commit_addition
+        let mut repositories = BTreeMap::new();
+        for estimate in session.commits.iter() {
+            // Time leading up to a commit is spent in the repository of that commit.
+            *repositories
+                .entry(estimate.commit.repository.clone())
+                .or_insert_with(Duration::zero) += estimate.duration;
+            if let Some(weights) = &estimate.commit.components {
+                for (component, part) in split_duration(estimate.duration, weights) {
+                    *components.entry(component).or_insert_with(Duration::zero) += part;
+                }
+            }
         }
-    });
+
+        sessions.push(WorkSession {
+            email: email.clone(),
+            author_name: author_name.clone(),
+            start: Utc.timestamp(first.commit.commit.time().seconds(), 0),
+            end: Utc.timestamp(last.commit.commit.time().seconds(), 0),
+            commits: session
+                .commits
+                .iter()
+                .map(|estimate| estimate.commit.commit.id())
+                .collect(),
+            first_commit_addition: first.duration,
+            estimated_duration: session.duration(),
+            commit_durations: session
+                .commits
+                .iter()
+                .map(|estimate| CommitDuration {
+                    repository: estimate.commit.repository.clone(),
+                    time: Utc.timestamp(estimate.commit.commit.time().seconds(), 0),
+                    duration: estimate.duration,
+                })
+                .collect(),
+            repositories,
+        });
+    }
+
+    let duration = estimate.duration();
+
+    let mut repositories: BTreeMap<String, RepositoryHours> = BTreeMap::new();
+    for commit in commits.iter() {
+        repositories
+            .entry(commit.repository.clone())
+            .or_default()
+            .commit_count += 1;
+    }
+    for session in sessions.iter() {
+        for (repository, duration) in session.repositories.iter() {
+            let hours = repositories.entry(repository.clone()).or_default();
+            hours.duration += *duration;
+        }
+    }
 
     CommitHours {
         email,
         author_name,
         duration,
         commit_count: commits.len(),
+        sessions,
+        repositories,
+        components,
     }
 }
 
-/// Collect time estimates by author.
-fn estimate_author_times(configuration: &Configuration, commits: Vec<Commit>) -> Vec<CommitHours> {
-    let mut no_email: Vec<&Commit> = Vec::new();
-    let mut by_email: HashMap<String, Vec<&Commit>> = HashMap::new();
+/// Collect time estimates by author with the estimator of the configuration.
+///
+/// Author identities are resolved through the email aliases of the configuration, on top of the
+/// authors resolved by `resolve_commits`.
+pub fn estimate_author_times(
+    configuration: &Configuration,
+    commits: Vec<RepositoryCommit>,
+) -> Vec<CommitHours> {
+    let estimator = configuration.estimator.estimator();
+    estimate_author_times_with(estimator.as_ref(), configuration, commits)
+}
+
+/// Collect time estimates by author with any estimator, e.g. one implemented outside this crate.
+pub fn estimate_author_times_with(
+    estimator: &dyn Estimator,
+    configuration: &Configuration,
+    commits: Vec<RepositoryCommit>,
+) -> Vec<CommitHours> {
+    let mut no_email: (Option<String>, Vec<&RepositoryCommit>) = (None, Vec::new());
+    let mut by_email: HashMap<String, (Option<String>, Vec<&RepositoryCommit>)> = HashMap::new();
     for commit in &commits {
-        let author = commit.author();
+        let author = &commit.author;
         let email = author
             .email()
             .map(|e| match configuration.email_aliases.get(e) {
@@ -171,29 +482,34 @@ fn estimate_author_times(configuration: &Configuration, commits: Vec<Commit>) ->
                 None => e,
             });
 
-        let author_commits = match email {
+        let (author_name, author_commits) = match email {
             Some(e) => by_email.entry(e.to_string()).or_default(),
             None => &mut no_email,
         };
 
+        if author_commits.is_empty() {
+            *author_name = author.name().map(|n| n.to_string());
+        }
         author_commits.push(commit);
     }
 
     let mut result = Vec::new();
-    if !no_email.is_empty() {
+    if !no_email.1.is_empty() {
         result.push(estimate_author_time(
-            no_email,
+            estimator,
+            no_email.1,
             None,
-            &configuration.max_commit_diff,
-            &configuration.first_commit_addition,
+            no_email.0,
+            configuration,
         ));
     }
-    for (email, author_commits) in by_email {
+    for (email, (author_name, author_commits)) in by_email {
         result.push(estimate_author_time(
+            estimator,
             author_commits,
             Some(email),
-            &configuration.max_commit_diff,
-            &configuration.first_commit_addition,
+            author_name,
+            configuration,
         ));
     }
     result.sort_by(|a, b| {
@@ -207,6 +523,94 @@ fn estimate_author_times(configuration: &Configuration, commits: Vec<Commit>) ->
     result
 }
 
+/// Find the repositories at or below a directory.
+///
+/// Directories are not searched further once they are found to be a repository, and hidden
+/// directories and symbolic links are skipped so no repository is found twice.
+pub fn find_repositories(path_directory: &Path) -> Result<Vec<PathBuf>> {
+    if path_directory.join(".git").exists() {
+        return Ok(vec![path_directory.to_path_buf()]);
+    }
+
+    let mut entries = std::fs::read_dir(path_directory)
+        .map_err(|e| {
+            anyhow!(
+                "Failed to scan {:?} for repositories: {}",
+                path_directory,
+                e
+            )
+        })?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            entry
+                .file_type()
+                .map(|file_type| file_type.is_dir())
+                .unwrap_or(false)
+        })
+        .map(|entry| entry.path())
+        .filter(|path| {
+            !path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .map(|name| name.starts_with('.'))
+                .unwrap_or(false)
+        })
+        .collect::<Vec<_>>();
+    entries.sort();
+
+    let mut result = Vec::new();
+    for entry in entries {
+        result.extend(find_repositories(&entry)?);
+    }
+
+    Ok(result)
+}
+
+/// Names repositories at distinct paths are reported under.
+///
+/// A repository is named after its working directory, prefixed with as many parent directories as
+/// it takes to tell it apart from repositories of the same name, e.g. `a/app` and `b/app`.
+pub fn repository_names(paths: &[PathBuf]) -> Vec<String> {
+    let parts = paths
+        .iter()
+        .map(|path| {
+            path.components()
+                .filter_map(|component| match component {
+                    Component::Normal(part) => Some(part.to_string_lossy().into_owned()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut depths = vec![1; paths.len()];
+    loop {
+        let names = paths
+            .iter()
+            .zip(parts.iter().zip(depths.iter()))
+            .map(|(path, (parts, depth))| match parts.len() {
+                0 => path.display().to_string(),
+                len => parts[len.saturating_sub(*depth)..].join("/"),
+            })
+            .collect::<Vec<_>>();
+
+        let mut deeper = false;
+        for (i, name) in names.iter().enumerate() {
+            let duplicate = names
+                .iter()
+                .enumerate()
+                .any(|(j, other)| j != i && other == name);
+            if duplicate && depths[i] < parts[i].len() {
+                depths[i] += 1;
+                deeper = true;
+            }
+        }
+        if !deeper {
+            return names;
+        }
+    }
+}
+
 /// Get the git repository context - whether.
 pub fn get_git_context(path_directory: PathBuf) -> Result<Repository> {
     let repository = match Repository::discover(&path_directory) {
@@ -236,51 +640,420 @@ pub fn get_git_context(path_directory: PathBuf) -> Result<Repository> {
     Ok(repository)
 }
 
+/// Open the submodules of a repository recursively.
+///
+/// Submodules are named by their path below the repository named `name`. Submodules that are not
+/// initialized are skipped.
+pub fn open_submodules(repository: &Repository, name: &str) -> Result<Vec<(String, Repository)>> {
+    let mut result = Vec::new();
+    for submodule in repository.submodules()? {
+        let submodule_name = format!("{}/{}", name, submodule.path().display());
+        match submodule.open() {
+            Ok(submodule_repository) => {
+                let nested = open_submodules(&submodule_repository, &submodule_name)?;
+                result.push((submodule_name, submodule_repository));
+                result.extend(nested);
+            }
+            Err(e) => {
+                log::warn!("Skipping submodule '{}': {}", submodule_name, e.message());
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// A repository whose commits were estimated.
+#[derive(Clone, Debug)]
+pub struct AnalyzedRepository {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+/// Result of running statistics on a repository.
+#[derive(Clone, Debug)]
+pub struct Estimate {
+    /// Repositories the commits were read from, including submodules.
+    pub repositories: Vec<AnalyzedRepository>,
+    /// Time estimates by author, longest duration first.
+    pub authors: Vec<CommitHours>,
+    /// Bots left out of the estimate, empty when bots are included.
+    pub bots: Vec<CommitHours>,
+    /// Authors left out by the author filters of the configuration.
+    pub excluded_authors: Vec<CommitHours>,
+}
+
+/// Builder for running statistics on a repository without the command line interface.
+#[derive(Clone, Debug)]
+pub struct Statistics {
+    configuration: Configuration,
+}
+
+impl Statistics {
+    /// Create statistics for the repository at `git_repo_path` using the default configuration.
+    pub fn new(git_repo_path: impl AsRef<Path>) -> Self {
+        Self::from_configuration(Configuration {
+            git_repo_paths: vec![git_repo_path.as_ref().to_path_buf()],
+            ..Configuration::default()
+        })
+    }
+
+    /// Also analyze the repository at `git_repo_path`, merging commits of the same authors.
+    pub fn repository(mut self, git_repo_path: impl AsRef<Path>) -> Self {
+        self.configuration
+            .git_repo_paths
+            .push(git_repo_path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Include the commits of every submodule, recursively.
+    pub fn recurse_submodules(mut self, recurse_submodules: bool) -> Self {
+        self.configuration.recurse_submodules = recurse_submodules;
+        self
+    }
+
+    /// Create statistics from an existing configuration.
+    pub fn from_configuration(configuration: Configuration) -> Self {
+        Self { configuration }
+    }
+
+    /// Maximum difference between commits counted to one session.
+    pub fn max_commit_diff(mut self, max_commit_diff: Duration) -> Self {
+        self.configuration.max_commit_diff = max_commit_diff;
+        self
+    }
+
+    /// Time the first commit of a session adds to the total.
+    pub fn first_commit_addition(mut self, first_commit_addition: Duration) -> Self {
+        self.configuration.first_commit_addition = first_commit_addition;
+        self
+    }
+
+    /// Analyze data since a certain date.
+    pub fn since(mut self, since: CommitTimeBound) -> Self {
+        self.configuration.since = since;
+        self
+    }
+
+    /// Analyze data until a certain date.
+    pub fn until(mut self, until: CommitTimeBound) -> Self {
+        self.configuration.until = until;
+        self
+    }
+
+    /// How merge commits are counted.
+    pub fn merges(mut self, merges: MergeCommits) -> Self {
+        self.configuration.merges = merges;
+        self
+    }
+
+    /// Walk only the first parent of merge commits, i.e. the mainline history of each branch.
+    pub fn first_parent(mut self, first_parent: bool) -> Self {
+        self.configuration.first_parent = first_parent;
+        self
+    }
+
+    /// Model the time spent working is estimated with.
+    pub fn estimator(mut self, estimator: EstimatorKind) -> Self {
+        self.configuration.estimator = estimator;
+        self
+    }
+
+    /// Coefficients of the diff-weighted estimator.
+    pub fn diff_weights(mut self, diff_weights: DiffWeights) -> Self {
+        self.configuration.diff_weights = diff_weights;
+        self
+    }
+
+    /// Add nothing for the first commit of the first session of each author, like earlier versions.
+    pub fn legacy_first_session(mut self, legacy_first_session: bool) -> Self {
+        self.configuration.legacy_first_session = legacy_first_session;
+        self
+    }
+
+    /// Associate all commits that have the `other` email with the `main` email.
+    pub fn email_alias(mut self, other: impl Into<String>, main: impl Into<String>) -> Self {
+        self.configuration
+            .email_aliases
+            .insert(other.into(), main.into());
+        self
+    }
+
+    /// Report only the authors with an email or name matching `author`, a literal or a regex.
+    ///
+    /// Can be called several times to report several authors.
+    pub fn author(mut self, author: impl Into<String>) -> Self {
+        self.configuration.authors.push(author.into());
+        self
+    }
+
+    /// Leave out the authors with an email or name matching `author`, a literal or a regex.
+    pub fn exclude_author(mut self, author: impl Into<String>) -> Self {
+        self.configuration.exclude_authors.push(author.into());
+        self
+    }
+
+    /// Report bots and automation accounts like any other author instead of leaving them out.
+    pub fn include_bots(mut self, include_bots: bool) -> Self {
+        self.configuration.include_bots = include_bots;
+        self
+    }
+
+    /// Recognize bots by author patterns instead of the built-in `DEFAULT_BOTS`.
+    pub fn bots<S: Into<String>>(mut self, bots: impl IntoIterator<Item = S>) -> Self {
+        self.configuration.bots = bots.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Analyze only data on the specified branch, or the branches matching a glob like `release/*`.
+    ///
+    /// Can be called several times to analyze several branches.
+    pub fn branch(mut self, branch: impl Into<String>, branch_type: impl Into<BranchKind>) -> Self {
+        self.configuration.branches.push(branch.into());
+        self.configuration.branch_type = branch_type.into();
+        self
+    }
+
+    /// Skip the branches matching a glob like `wip/**`.
+    pub fn exclude_branch(mut self, branch: impl Into<String>) -> Self {
+        self.configuration.exclude_branches.push(branch.into());
+        self
+    }
+
+    /// Also analyze the history of every tag.
+    pub fn tags(mut self, tags: bool) -> Self {
+        self.configuration.tags = tags;
+        self
+    }
+
+    /// Analyze only the commits of a git revision or range, e.g. `v1.2.0..v1.3.0`.
+    ///
+    /// Revisions take the place of branches.
+    pub fn revision(mut self, revision: impl Into<String>) -> Self {
+        self.configuration.revisions.push(revision.into());
+        self
+    }
+
+    /// Analyze only commits touching paths that match a git pathspec, e.g. `src/backend`.
+    pub fn pathspec(mut self, pathspec: impl Into<String>) -> Self {
+        self.configuration.pathspecs.push(pathspec.into());
+        self
+    }
+
+    /// Break down the estimated time by component, weighted by the lines each commit changes.
+    ///
+    /// Components are the top-level directories unless named components are configured.
+    pub fn by_component(mut self, by_component: bool) -> Self {
+        self.configuration.by_component = by_component;
+        self
+    }
+
+    /// Name the component of the files matching path globs like `src/backend`.
+    pub fn component<S: Into<String>>(
+        mut self,
+        name: impl Into<String>,
+        patterns: impl IntoIterator<Item = S>,
+    ) -> Self {
+        self.configuration
+            .components
+            .insert(name.into(), patterns.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Resolve author identities through the repository `.mailmap`.
+    pub fn mailmap(mut self, mailmap: bool) -> Self {
+        self.configuration.mailmap = mailmap;
+        self
+    }
+
+    /// Configuration the statistics will run with.
+    pub fn configuration(&self) -> &Configuration {
+        &self.configuration
+    }
+
+    /// Run statistics on the repository.
+    pub fn run(&self) -> Result<Estimate> {
+        let configuration = &self.configuration;
+        configuration.validate()?;
+
+        let mut git_repo_paths = Vec::new();
+        for path in configuration.git_repo_paths.iter() {
+            if configuration.scan_repositories {
+                git_repo_paths.extend(find_repositories(path)?);
+            } else {
+                git_repo_paths.push(path.clone());
+            }
+        }
+        log::debug!("Repository Paths: {:?}", git_repo_paths);
+        log::debug!("");
+
+        // Paths like `.` and `./src` or symbolic links can lead to the same repository.
+        let mut opened = Vec::new();
+        let mut seen_paths = HashSet::new();
+        for path in git_repo_paths {
+            let repository = get_git_context(path)?;
+            let root = repository
+                .workdir()
+                .unwrap_or_else(|| repository.path())
+                .to_path_buf();
+            let root = root.canonicalize().unwrap_or(root);
+            if seen_paths.insert(root.clone()) {
+                opened.push((root, repository));
+            }
+        }
+        let names = repository_names(
+            &opened
+                .iter()
+                .map(|(root, _)| root.clone())
+                .collect::<Vec<_>>(),
+        );
+
+        let mut repositories = Vec::new();
+        for (name, (_, repository)) in names.into_iter().zip(opened) {
+            if configuration.recurse_submodules {
+                let submodules = open_submodules(&repository, &name)?;
+                repositories.push((name, repository));
+                repositories.extend(submodules);
+            } else {
+                repositories.push((name, repository));
+            }
+        }
+
+        let estimator = configuration.estimator.estimator();
+        let matcher = if configuration.by_component {
+            Some(ComponentMatcher::from_configuration(configuration)?)
+        } else {
+            None
+        };
+
+        let mut analyzed = Vec::new();
+        let mut commits_resolved = Vec::new();
+        let mut seen = HashSet::new();
+        for (name, repository) in repositories.iter() {
+            log::debug!("Repository: {} {:?}", name, repository.path());
+            log::debug!("");
+            analyzed.push(AnalyzedRepository {
+                name: name.clone(),
+                path: repository
+                    .workdir()
+                    .unwrap_or_else(|| repository.path())
+                    .to_path_buf(),
+            });
+
+            let commits = get_commits(repository, configuration)?;
+            log::debug!("Commits: {:?}", commits);
+            log::debug!("");
+
+            // The same commit can be reachable from several repositories, e.g. forks.
+            let commits = filter_commits(configuration, commits);
+            let commits_filtered = filter_commits_by_pathspec(repository, configuration, commits)?
+                .into_iter()
+                .filter(|commit| seen.insert(commit.id()))
+                .collect();
+            log::debug!("Commits Filtered: {:?}", commits_filtered);
+            log::debug!("");
+
+            let mailmap = if configuration.mailmap {
+                Some(repository.mailmap()?)
+            } else {
+                None
+            };
+
+            let mut commits = resolve_commits(name, commits_filtered, mailmap.as_ref())?;
+            if estimator.needs_line_stats() {
+                for commit in commits.iter_mut() {
+                    commit.line_stats = Some(commit_line_stats(
+                        repository,
+                        &commit.commit,
+                        configuration,
+                    )?);
+                }
+            }
+            if let Some(matcher) = &matcher {
+                for commit in commits.iter_mut() {
+                    commit.components = Some(commit_components(
+                        repository,
+                        &commit.commit,
+                        configuration,
+                        matcher,
+                    )?);
+                }
+            }
+            commits_resolved.extend(commits);
+        }
+
+        let (authors, bots) = partition_bots(
+            configuration,
+            estimate_author_times_with(estimator.as_ref(), configuration, commits_resolved),
+        )?;
+        let filter = AuthorFilter::from_configuration(configuration)?;
+        let (authors, excluded_authors): (Vec<_>, Vec<_>) =
+            authors.into_iter().partition(|time| filter.includes(time));
+        log::debug!("Estimate: {:?}", authors);
+        log::debug!("Bots: {:?}", bots);
+        log::debug!("");
+
+        Ok(Estimate {
+            repositories: analyzed,
+            authors,
+            bots,
+            excluded_authors,
+        })
+    }
+}
+
 /// Run statistics on repository.
 pub fn statistics(args: &clap::ArgMatches) -> Result<ExitCode> {
-    let configuration = &parse_arguments(args)?;
+    let configuration = parse_arguments(args)?;
     log::debug!("{:?}", configuration);
     log::debug!("");
 
-    let repository = get_git_context(configuration.git_repo_path.clone())?;
-    log::debug!("Repository: {:?}", repository.path());
-    log::debug!("");
+    // Refuse to overwrite an output file before spending time on the estimate.
+    let mut writer = ReportWriter::from_configuration(&configuration)?;
 
-    let commits = get_commits(
-        &repository,
-        &configuration.branch,
-        configuration.branch_type,
-    )?;
-    log::debug!("Commits: {:?}", commits);
-    log::debug!("");
+    let statistics = Statistics::from_configuration(configuration);
+    let estimate = statistics.run()?;
+    let configuration = statistics.configuration();
 
-    let commits_filtered = filter_commits(configuration, commits);
-    log::debug!("Commits Filtered: {:?}", commits_filtered);
-    log::debug!("");
-
-    let estimate_by_author = estimate_author_times(configuration, commits_filtered);
-    log::debug!("Estimate: {:?}", estimate_by_author);
-    log::debug!("");
-
-    if estimate_by_author.is_empty() {
-        match &configuration.branch {
-            Some(b) => {
-                let branch_type = match configuration.branch_type {
-                    BranchType::Local => "local",
-                    BranchType::Remote => "remote",
-                };
-                return Err(anyhow!(
-                    "No commits found for branch '{}' ({}).",
-                    b,
-                    branch_type
+    if estimate.authors.is_empty() {
+        if !estimate.excluded_authors.is_empty() || !estimate.bots.is_empty() {
+            let mut filters = Vec::new();
+            if !configuration.authors.is_empty() {
+                filters.push(format!("author '{}'", configuration.authors.join("', '")));
+            }
+            if !configuration.exclude_authors.is_empty() {
+                filters.push(format!(
+                    "exclude-author '{}'",
+                    configuration.exclude_authors.join("', '")
                 ));
             }
-            None => {
-                return Err(anyhow!("No commits found.",));
+            if !estimate.bots.is_empty() {
+                filters.push(format!(
+                    "{} bots excluded, see --include-bots",
+                    estimate.bots.len()
+                ));
             }
+            return Err(anyhow!(
+                "No authors matched the filters: {}.",
+                filters.join("; ")
+            ));
+        } else if !configuration.branches.is_empty() {
+            return Err(anyhow!(
+                "No commits found for branch '{}' ({}).",
+                configuration.branches.join("', '"),
+                configuration.branch_type
+            ));
+        } else if !configuration.revisions.is_empty() {
+            return Err(anyhow!(
+                "No commits found for revisions '{}'.",
+                configuration.revisions.join("', '")
+            ));
+        } else {
+            return Err(anyhow!("No commits found.",));
         }
     } else {
-        print_results(&estimate_by_author, &configuration.output_format)?;
+        print_results(&mut writer, &estimate, configuration)?;
+        writer.finish()?;
     };
 
     log::debug!("Done.");
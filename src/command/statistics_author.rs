@@ -0,0 +1,111 @@
+use super::statistics_configuration::Configuration;
+use crate::git::CommitHours;
+use anyhow::Result;
+use regex::{Regex, RegexBuilder};
+
+/// Author patterns of bots and automation accounts excluded from estimates by default.
+pub const DEFAULT_BOTS: [&str; 7] = [
+    r".*\[bot\].*",
+    "dependabot(-preview)?",
+    "renovate(-bot)?",
+    r".*@renovateapp\.com",
+    "github-actions",
+    "greenkeeper(io)?",
+    ".*[-_ ]bot",
+];
+
+/// Author email, name or regex.
+///
+/// Patterns match the whole email or name of an author, ignoring case, so `.*@example\.com` matches
+/// every author with an `example.com` email.
+#[derive(Clone, Debug)]
+pub struct AuthorPattern {
+    pattern: String,
+    rx: Regex,
+}
+
+impl AuthorPattern {
+    pub fn new(pattern: &str) -> Result<Self, regex::Error> {
+        let rx = RegexBuilder::new(&format!("^(?:{})$", pattern))
+            .case_insensitive(true)
+            .build()?;
+
+        Ok(Self {
+            pattern: pattern.to_string(),
+            rx,
+        })
+    }
+
+    /// Whether the pattern is the email or name of an author, or a regex matching either.
+    pub fn matches(&self, email: Option<&str>, author_name: Option<&str>) -> bool {
+        // Emails like `nate+ci@example.com` are not matched by their own regex.
+        [email, author_name].iter().flatten().any(|identity| {
+            identity.eq_ignore_ascii_case(&self.pattern) || self.rx.is_match(identity)
+        })
+    }
+}
+
+/// Authors reported by an estimate.
+#[derive(Clone, Debug, Default)]
+pub struct AuthorFilter {
+    authors: Vec<AuthorPattern>,
+    exclude_authors: Vec<AuthorPattern>,
+}
+
+impl AuthorFilter {
+    /// Filter for the authors and excluded authors of the configuration.
+    pub fn from_configuration(configuration: &Configuration) -> Result<Self> {
+        let patterns = |patterns: &[String]| -> Result<Vec<AuthorPattern>> {
+            Ok(patterns
+                .iter()
+                .map(|pattern| AuthorPattern::new(pattern))
+                .collect::<Result<_, _>>()?)
+        };
+
+        Ok(Self {
+            authors: patterns(&configuration.authors)?,
+            exclude_authors: patterns(&configuration.exclude_authors)?,
+        })
+    }
+
+    /// Whether the author of an estimate is reported.
+    pub fn includes(&self, time: &CommitHours) -> bool {
+        let email = time.email.as_deref();
+        let author_name = time.author_name.as_deref();
+
+        let included = self.authors.is_empty()
+            || self
+                .authors
+                .iter()
+                .any(|pattern| pattern.matches(email, author_name));
+        included
+            && !self
+                .exclude_authors
+                .iter()
+                .any(|pattern| pattern.matches(email, author_name))
+    }
+}
+
+/// Split the authors into the authors reported and the bots left out of the estimate.
+///
+/// Every author is reported when the configuration includes bots.
+pub fn partition_bots(
+    configuration: &Configuration,
+    authors: Vec<CommitHours>,
+) -> Result<(Vec<CommitHours>, Vec<CommitHours>)> {
+    if configuration.include_bots {
+        return Ok((authors, Vec::new()));
+    }
+
+    let bots = configuration
+        .bots
+        .iter()
+        .map(|pattern| AuthorPattern::new(pattern))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(authors.into_iter().partition(|time| {
+        !bots
+            .iter()
+            .any(|bot| bot.matches(time.email.as_deref(), time.author_name.as_deref()))
+    }))
+}
@@ -0,0 +1,147 @@
+use super::statistics::{diff_to_first_parent, glob_to_regex};
+use super::statistics_configuration::Configuration;
+use anyhow::Result;
+use chrono::Duration;
+use git2::{Commit, Patch, Repository};
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Component of files in the repository root when components are directories.
+pub const ROOT_COMPONENT: &str = "(root)";
+
+/// Component of changed files that no configured component matches.
+pub const OTHER_COMPONENT: &str = "(other)";
+
+/// Component of commits without any changed file, e.g. empty commits.
+pub const NO_COMPONENT: &str = "(none)";
+
+/// Assigns changed files to components.
+#[derive(Clone, Debug)]
+pub enum ComponentMatcher {
+    /// Directories up to a depth, `1` being the top-level directories.
+    Depth(usize),
+    /// Named components of path globs, checked in order.
+    Map(Vec<(String, Vec<Regex>)>),
+}
+
+impl ComponentMatcher {
+    /// Matcher for the components of the configuration.
+    pub fn from_configuration(configuration: &Configuration) -> Result<Self> {
+        if configuration.components.is_empty() {
+            return Ok(Self::Depth(configuration.component_depth));
+        }
+
+        let mut components = Vec::new();
+        for (name, patterns) in configuration.components.iter() {
+            let patterns = patterns
+                .iter()
+                .map(|pattern| {
+                    // A pattern also matches everything below the directories it matches.
+                    let pattern = pattern.trim_end_matches('/');
+                    Regex::new(&format!("^{}(/.*)?$", glob_to_regex(pattern)))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            components.push((name.clone(), patterns));
+        }
+
+        Ok(Self::Map(components))
+    }
+
+    /// Component of a file path relative to the repository root.
+    pub fn component(&self, path: &Path) -> String {
+        match self {
+            Self::Depth(depth) => {
+                let directories = path
+                    .parent()
+                    .map(|parent| {
+                        parent
+                            .components()
+                            .take(*depth)
+                            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                            .collect::<Vec<_>>()
+                    })
+                    .unwrap_or_default();
+                if directories.is_empty() {
+                    String::from(ROOT_COMPONENT)
+                } else {
+                    directories.join("/")
+                }
+            }
+            Self::Map(components) => {
+                let path = path.to_string_lossy();
+                components
+                    .iter()
+                    .find(|(_, patterns)| patterns.iter().any(|rx| rx.is_match(&path)))
+                    .map(|(name, _)| name.clone())
+                    .unwrap_or_else(|| String::from(OTHER_COMPONENT))
+            }
+        }
+    }
+}
+
+/// Lines changed by a commit against its first parent, by component.
+///
+/// Files without changed lines, e.g. binary files, count as one line.
+pub fn commit_components(
+    repo: &Repository,
+    commit: &Commit,
+    configuration: &Configuration,
+    matcher: &ComponentMatcher,
+) -> Result<BTreeMap<String, usize>> {
+    let diff = diff_to_first_parent(repo, commit, &configuration.pathspecs)?;
+
+    let mut components = BTreeMap::new();
+    for (i, delta) in diff.deltas().enumerate() {
+        let path = match delta.new_file().path().or_else(|| delta.old_file().path()) {
+            Some(path) => path,
+            None => continue,
+        };
+        let lines = match Patch::from_diff(&diff, i)? {
+            Some(patch) => {
+                let (_, additions, deletions) = patch.line_stats()?;
+                additions + deletions
+            }
+            None => 0,
+        };
+
+        *components.entry(matcher.component(path)).or_insert(0) += lines.max(1);
+    }
+
+    Ok(components)
+}
+
+/// Split a duration across components in proportion to their weights.
+///
+/// The rounding remainder goes to the heaviest component so the parts add up to the duration.
+pub fn split_duration(
+    duration: Duration,
+    weights: &BTreeMap<String, usize>,
+) -> BTreeMap<String, Duration> {
+    let total = weights.values().sum::<usize>() as i64;
+    if total == 0 {
+        return vec![(String::from(NO_COMPONENT), duration)]
+            .into_iter()
+            .collect();
+    }
+
+    let seconds = duration.num_seconds();
+    let mut parts = weights
+        .iter()
+        .map(|(component, weight)| {
+            let part = seconds * (*weight as i64) / total;
+            (component.clone(), Duration::seconds(part))
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    let assigned = parts
+        .values()
+        .fold(Duration::zero(), |acc, part| acc + *part);
+    if let Some((heaviest, _)) = weights.iter().max_by_key(|(_, weight)| **weight) {
+        if let Some(part) = parts.get_mut(heaviest) {
+            *part += duration - assigned;
+        }
+    }
+
+    parts
+}
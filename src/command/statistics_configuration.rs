@@ -1,37 +1,645 @@
 #![allow(deprecated)]
 
+use super::statistics_author::{AuthorPattern, DEFAULT_BOTS};
+use super::statistics_configuration_file::{
+    repository_configuration_path, ConfigurationSource, ConfigurationValues, LayeredConfiguration,
+};
+use super::statistics_estimator::{DiffWeights, EstimatorKind};
+use crate::error::{self, ErrorKind};
 use crate::git::*;
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, Result};
 use chrono::Duration;
-use clap::{arg_enum, value_t};
+use clap::arg_enum;
 use git2::BranchType;
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 clap::arg_enum! {
-    #[derive(PartialEq, Debug)]
+    #[derive(Clone, PartialEq, Debug)]
     pub enum OutputFormat {
         Stdout,
-        Json
+        Json,
+        Csv,
+        Tsv,
+        Markdown,
+        Html
     }
 }
 
-#[derive(Debug)]
+clap::arg_enum! {
+    #[derive(Clone, Copy, PartialEq, Debug)]
+    pub enum GroupBy {
+        Day,
+        Week,
+        Month,
+        Year
+    }
+}
+
+/// Kind of branches analyzed.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum BranchKind {
+    /// Branches under `refs/heads/`.
+    Local,
+    /// Branches under `refs/remotes/`.
+    Remote,
+    /// Both local and remote branches.
+    All,
+}
+
+impl BranchKind {
+    pub fn variants() -> [&'static str; 3] {
+        ["local", "remote", "all"]
+    }
+
+    /// Reference prefixes the branches of this kind live under.
+    pub fn ref_prefixes(&self) -> &'static [&'static str] {
+        match self {
+            Self::Local => &["refs/heads"],
+            Self::Remote => &["refs/remotes"],
+            Self::All => &["refs/heads", "refs/remotes"],
+        }
+    }
+}
+
+impl From<BranchType> for BranchKind {
+    fn from(branch_type: BranchType) -> Self {
+        match branch_type {
+            BranchType::Local => Self::Local,
+            BranchType::Remote => Self::Remote,
+        }
+    }
+}
+
+impl FromStr for BranchKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "local" => Ok(Self::Local),
+            "remote" => Ok(Self::Remote),
+            "all" => Ok(Self::All),
+            _ => Err(anyhow!("Invalid branch type '{}'", s)),
+        }
+    }
+}
+
+impl fmt::Display for BranchKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Local => "local",
+            Self::Remote => "remote",
+            Self::All => "all",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// How merge commits are counted.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum MergeCommits {
+    /// Exclude commits with more than one parent.
+    Exclude,
+    /// Include merge commits like any other commit.
+    Include,
+    /// Include merge commits but only follow the first parent of every merge.
+    FirstParent,
+    /// Exclude commits whose summary starts with "Merge ", as earlier versions did.
+    Message,
+}
+
+impl MergeCommits {
+    pub fn variants() -> [&'static str; 4] {
+        ["exclude", "include", "first-parent", "message"]
+    }
+}
+
+impl FromStr for MergeCommits {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "exclude" => Ok(Self::Exclude),
+            "include" => Ok(Self::Include),
+            "first-parent" => Ok(Self::FirstParent),
+            "message" => Ok(Self::Message),
+            _ => Err(anyhow!(
+                "Invalid merge mode '{}', expected one of {}",
+                s,
+                Self::variants().join(", ")
+            )),
+        }
+    }
+}
+
+impl fmt::Display for MergeCommits {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Exclude => "exclude",
+            Self::Include => "include",
+            Self::FirstParent => "first-parent",
+            Self::Message => "message",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl OutputFormat {
+    /// Format implied by the extension of an output file, if any.
+    pub fn from_extension(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "json" => Some(Self::Json),
+            "csv" => Some(Self::Csv),
+            "tsv" => Some(Self::Tsv),
+            "md" | "markdown" => Some(Self::Markdown),
+            "html" | "htm" => Some(Self::Html),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
 pub struct Configuration {
+    /// Model the time spent working is estimated with.
+    pub estimator: EstimatorKind,
+    /// Coefficients of the diff-weighted estimator.
+    pub diff_weights: DiffWeights,
     pub max_commit_diff: Duration,
     pub first_commit_addition: Duration,
+    /// Add nothing for the first commit of the first session of each author, like earlier versions.
+    pub legacy_first_session: bool,
     pub since: CommitTimeBound,
     pub until: CommitTimeBound,
-    pub merge_requests: bool,
-    pub git_repo_path: PathBuf,
+    pub merges: MergeCommits,
+    /// Follow only the first parent of merge commits.
+    pub first_parent: bool,
+    pub git_repo_paths: Vec<PathBuf>,
+    pub scan_repositories: bool,
+    pub recurse_submodules: bool,
     pub email_aliases: HashMap<String, String>,
-    pub branch: Option<String>,
-    pub branch_type: BranchType,
+    pub mailmap: bool,
+    /// Author emails, names or regexes to report, every author when empty.
+    pub authors: Vec<String>,
+    /// Author emails, names or regexes to leave out of the report.
+    pub exclude_authors: Vec<String>,
+    /// Author patterns of bots and automation accounts.
+    pub bots: Vec<String>,
+    /// Report bots like any other author instead of leaving them out.
+    pub include_bots: bool,
+    /// Branch names or globs to analyze, all branches when empty.
+    pub branches: Vec<String>,
+    /// Branch names or globs to skip.
+    pub exclude_branches: Vec<String>,
+    /// Also analyze the history of every tag.
+    pub tags: bool,
+    pub branch_type: BranchKind,
+    /// Git revisions and ranges analyzed instead of branches.
+    pub revisions: Vec<String>,
+    /// Git pathspecs that analyzed commits must touch, all commits when empty.
+    pub pathspecs: Vec<String>,
     pub output_format: OutputFormat,
+    pub totals: bool,
+    pub sessions: bool,
+    pub by_repository: bool,
+    /// Break down estimated hours by component, weighted by lines changed.
+    pub by_component: bool,
+    /// Directory depth of components when no component map is configured.
+    pub component_depth: usize,
+    /// Path globs by component name, checked in order of the names.
+    pub components: BTreeMap<String, Vec<String>>,
+    pub group_by: Option<GroupBy>,
+    pub time_zone: ReportTimeZone,
+    pub json_legacy: bool,
+    /// File the report is written to instead of stdout.
+    pub output: Option<PathBuf>,
+    /// Overwrite an existing output file.
+    pub force: bool,
 }
 
-fn parse_email_alias(s: &str) -> Result<(String, String)> {
+impl Default for Configuration {
+    fn default() -> Self {
+        Self {
+            estimator: EstimatorKind::GitHours,
+            diff_weights: DiffWeights::default(),
+            max_commit_diff: Duration::minutes(120),
+            first_commit_addition: Duration::minutes(30),
+            legacy_first_session: false,
+            since: CommitTimeBound::Always,
+            until: CommitTimeBound::Always,
+            merges: MergeCommits::Exclude,
+            first_parent: false,
+            git_repo_paths: vec![PathBuf::from(".")],
+            scan_repositories: false,
+            recurse_submodules: false,
+            email_aliases: HashMap::new(),
+            mailmap: true,
+            authors: Vec::new(),
+            exclude_authors: Vec::new(),
+            bots: DEFAULT_BOTS.iter().map(|bot| bot.to_string()).collect(),
+            include_bots: false,
+            branches: Vec::new(),
+            exclude_branches: Vec::new(),
+            tags: false,
+            branch_type: BranchKind::Local,
+            revisions: Vec::new(),
+            pathspecs: Vec::new(),
+            output_format: OutputFormat::Stdout,
+            totals: true,
+            sessions: false,
+            by_repository: false,
+            by_component: false,
+            component_depth: 1,
+            components: BTreeMap::new(),
+            group_by: None,
+            time_zone: ReportTimeZone::Local,
+            json_legacy: false,
+            output: None,
+            force: false,
+        }
+    }
+}
+
+impl Configuration {
+    /// Create a builder starting from the default configuration.
+    pub fn builder() -> ConfigurationBuilder {
+        ConfigurationBuilder::default()
+    }
+
+    /// Whether history is walked along the first parent of merge commits only.
+    pub fn follows_first_parent(&self) -> bool {
+        self.first_parent || self.merges == MergeCommits::FirstParent
+    }
+
+    /// Check that the configuration describes a runnable estimate.
+    pub fn validate(&self) -> Result<(), error::Error> {
+        let invalid = |message: String| Err(error::Error::new(ErrorKind::Configuration(message)));
+
+        if self.git_repo_paths.is_empty() {
+            return invalid(String::from("At least one repository path is required."));
+        }
+        if self.max_commit_diff <= Duration::zero() {
+            return invalid(format!(
+                "Max commit diff must be greater than zero, got {} minutes.",
+                self.max_commit_diff.num_minutes()
+            ));
+        }
+        let weights = &self.diff_weights;
+        if weights.reference_lines == 0 {
+            return invalid(String::from("Diff reference lines must be at least 1."));
+        }
+        if !(weights.exponent >= 0.0
+            && weights.max_factor >= 0.0
+            && weights.minutes_per_line >= 0.0)
+        {
+            return invalid(String::from(
+                "Diff exponent, max factor and minutes per line must not be negative.",
+            ));
+        }
+        if self.first_commit_addition < Duration::zero() {
+            return invalid(format!(
+                "First commit add must not be negative, got {} minutes.",
+                self.first_commit_addition.num_minutes()
+            ));
+        }
+        if let (Some(since), Some(until)) = (self.since.to_date_time(), self.until.to_date_time()) {
+            if since > until {
+                return invalid(format!(
+                    "Since '{}' must not be after until '{}'.",
+                    self.since, self.until
+                ));
+            }
+        }
+        for (other, main) in self.email_aliases.iter() {
+            if other.is_empty() || main.is_empty() {
+                return invalid(format!(
+                    "Email alias '{}={}' must not be empty.",
+                    other, main
+                ));
+            }
+        }
+        if self.sessions && self.group_by.is_some() {
+            return invalid(String::from(
+                "Sessions and group by cannot be combined in one report.",
+            ));
+        }
+        if self.by_component && (self.sessions || self.group_by.is_some() || self.by_repository) {
+            return invalid(String::from(
+                "Components cannot be combined with sessions, group by or repositories in one report.",
+            ));
+        }
+        if self.component_depth == 0 {
+            return invalid(String::from("Component depth must be at least 1."));
+        }
+        for (name, patterns) in self.components.iter() {
+            if name.is_empty() || patterns.is_empty() || patterns.iter().any(|p| p.is_empty()) {
+                return invalid(format!(
+                    "Component '{}' must have a name and non-empty path patterns.",
+                    name
+                ));
+            }
+        }
+        for author in self.authors.iter().chain(self.exclude_authors.iter()) {
+            if author.is_empty() {
+                return invalid(String::from("Author must not be empty."));
+            }
+            if let Err(e) = AuthorPattern::new(author) {
+                return invalid(format!("Invalid author pattern '{}': {}", author, e));
+            }
+        }
+        for bot in self.bots.iter() {
+            if bot.is_empty() {
+                return invalid(String::from("Bot must not be empty."));
+            }
+            if let Err(e) = AuthorPattern::new(bot) {
+                return invalid(format!("Invalid bot pattern '{}': {}", bot, e));
+            }
+        }
+        let branches = self.branches.iter().chain(self.exclude_branches.iter());
+        if branches.into_iter().any(|branch| branch.is_empty()) {
+            return invalid(String::from("Branch must not be empty."));
+        }
+        if self.revisions.iter().any(|revision| revision.is_empty()) {
+            return invalid(String::from("Revisions must not be empty."));
+        }
+        if !self.branches.is_empty() && !self.revisions.is_empty() {
+            return invalid(String::from(
+                "A branch and revisions cannot be analyzed at the same time.",
+            ));
+        }
+        if !self.revisions.is_empty()
+            && (!self.exclude_branches.is_empty()
+                || self.tags
+                || self.branch_type != BranchKind::Local)
+        {
+            return invalid(String::from(
+                "Excluded branches, tags and branch types cannot be combined with revisions.",
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Builder for a validated `Configuration`.
+#[derive(Clone, Debug, Default)]
+pub struct ConfigurationBuilder {
+    configuration: Configuration,
+}
+
+impl ConfigurationBuilder {
+    pub fn estimator(mut self, estimator: EstimatorKind) -> Self {
+        self.configuration.estimator = estimator;
+        self
+    }
+
+    pub fn diff_weights(mut self, diff_weights: DiffWeights) -> Self {
+        self.configuration.diff_weights = diff_weights;
+        self
+    }
+
+    pub fn max_commit_diff(mut self, max_commit_diff: Duration) -> Self {
+        self.configuration.max_commit_diff = max_commit_diff;
+        self
+    }
+
+    pub fn first_commit_addition(mut self, first_commit_addition: Duration) -> Self {
+        self.configuration.first_commit_addition = first_commit_addition;
+        self
+    }
+
+    pub fn legacy_first_session(mut self, legacy_first_session: bool) -> Self {
+        self.configuration.legacy_first_session = legacy_first_session;
+        self
+    }
+
+    pub fn since(mut self, since: CommitTimeBound) -> Self {
+        self.configuration.since = since;
+        self
+    }
+
+    pub fn until(mut self, until: CommitTimeBound) -> Self {
+        self.configuration.until = until;
+        self
+    }
+
+    pub fn merges(mut self, merges: MergeCommits) -> Self {
+        self.configuration.merges = merges;
+        self
+    }
+
+    pub fn first_parent(mut self, first_parent: bool) -> Self {
+        self.configuration.first_parent = first_parent;
+        self
+    }
+
+    pub fn git_repo_path(mut self, git_repo_path: impl Into<PathBuf>) -> Self {
+        self.configuration.git_repo_paths = vec![git_repo_path.into()];
+        self
+    }
+
+    pub fn git_repo_paths<P: Into<PathBuf>>(
+        mut self,
+        git_repo_paths: impl IntoIterator<Item = P>,
+    ) -> Self {
+        self.configuration.git_repo_paths = git_repo_paths.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn scan_repositories(mut self, scan_repositories: bool) -> Self {
+        self.configuration.scan_repositories = scan_repositories;
+        self
+    }
+
+    pub fn recurse_submodules(mut self, recurse_submodules: bool) -> Self {
+        self.configuration.recurse_submodules = recurse_submodules;
+        self
+    }
+
+    pub fn email_alias(mut self, other: impl Into<String>, main: impl Into<String>) -> Self {
+        self.configuration
+            .email_aliases
+            .insert(other.into(), main.into());
+        self
+    }
+
+    pub fn mailmap(mut self, mailmap: bool) -> Self {
+        self.configuration.mailmap = mailmap;
+        self
+    }
+
+    pub fn authors<S: Into<String>>(mut self, authors: impl IntoIterator<Item = S>) -> Self {
+        self.configuration.authors = authors.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn exclude_authors<S: Into<String>>(
+        mut self,
+        exclude_authors: impl IntoIterator<Item = S>,
+    ) -> Self {
+        self.configuration.exclude_authors = exclude_authors.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn bots<S: Into<String>>(mut self, bots: impl IntoIterator<Item = S>) -> Self {
+        self.configuration.bots = bots.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn include_bots(mut self, include_bots: bool) -> Self {
+        self.configuration.include_bots = include_bots;
+        self
+    }
+
+    pub fn branches<S: Into<String>>(mut self, branches: impl IntoIterator<Item = S>) -> Self {
+        self.configuration.branches = branches.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn exclude_branches<S: Into<String>>(
+        mut self,
+        exclude_branches: impl IntoIterator<Item = S>,
+    ) -> Self {
+        self.configuration.exclude_branches =
+            exclude_branches.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn tags(mut self, tags: bool) -> Self {
+        self.configuration.tags = tags;
+        self
+    }
+
+    pub fn branch_type(mut self, branch_type: BranchKind) -> Self {
+        self.configuration.branch_type = branch_type;
+        self
+    }
+
+    pub fn revisions<S: Into<String>>(mut self, revisions: impl IntoIterator<Item = S>) -> Self {
+        self.configuration.revisions = revisions.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn pathspecs<S: Into<String>>(mut self, pathspecs: impl IntoIterator<Item = S>) -> Self {
+        self.configuration.pathspecs = pathspecs.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn output_format(mut self, output_format: OutputFormat) -> Self {
+        self.configuration.output_format = output_format;
+        self
+    }
+
+    pub fn totals(mut self, totals: bool) -> Self {
+        self.configuration.totals = totals;
+        self
+    }
+
+    pub fn sessions(mut self, sessions: bool) -> Self {
+        self.configuration.sessions = sessions;
+        self
+    }
+
+    pub fn by_repository(mut self, by_repository: bool) -> Self {
+        self.configuration.by_repository = by_repository;
+        self
+    }
+
+    pub fn by_component(mut self, by_component: bool) -> Self {
+        self.configuration.by_component = by_component;
+        self
+    }
+
+    pub fn component_depth(mut self, component_depth: usize) -> Self {
+        self.configuration.component_depth = component_depth;
+        self
+    }
+
+    pub fn components(mut self, components: BTreeMap<String, Vec<String>>) -> Self {
+        self.configuration.components = components;
+        self
+    }
+
+    pub fn group_by(mut self, group_by: Option<GroupBy>) -> Self {
+        self.configuration.group_by = group_by;
+        self
+    }
+
+    pub fn time_zone(mut self, time_zone: ReportTimeZone) -> Self {
+        self.configuration.time_zone = time_zone;
+        self
+    }
+
+    pub fn json_legacy(mut self, json_legacy: bool) -> Self {
+        self.configuration.json_legacy = json_legacy;
+        self
+    }
+
+    pub fn output(mut self, output: Option<PathBuf>) -> Self {
+        self.configuration.output = output;
+        self
+    }
+
+    pub fn force(mut self, force: bool) -> Self {
+        self.configuration.force = force;
+        self
+    }
+
+    /// Validate and return the configuration.
+    pub fn build(self) -> Result<Configuration, error::Error> {
+        self.configuration.validate()?;
+        Ok(self.configuration)
+    }
+}
+
+impl From<&Configuration> for ConfigurationJson {
+    fn from(configuration: &Configuration) -> Self {
+        ConfigurationJson {
+            estimator: configuration.estimator.to_string(),
+            diff_reference_lines: configuration.diff_weights.reference_lines,
+            diff_exponent: configuration.diff_weights.exponent,
+            diff_max_factor: configuration.diff_weights.max_factor,
+            diff_minutes_per_line: configuration.diff_weights.minutes_per_line,
+            max_commit_diff_minutes: configuration.max_commit_diff.num_minutes(),
+            first_commit_addition_minutes: configuration.first_commit_addition.num_minutes(),
+            legacy_first_session: configuration.legacy_first_session,
+            since: configuration.since.to_string(),
+            until: configuration.until.to_string(),
+            merges: configuration.merges.to_string(),
+            first_parent: configuration.first_parent,
+            recurse_submodules: configuration.recurse_submodules,
+            email_aliases: configuration
+                .email_aliases
+                .iter()
+                .map(|(other, main)| (other.clone(), main.clone()))
+                .collect(),
+            mailmap: configuration.mailmap,
+            authors: configuration.authors.clone(),
+            exclude_authors: configuration.exclude_authors.clone(),
+            bots: configuration.bots.clone(),
+            include_bots: configuration.include_bots,
+            branches: configuration.branches.clone(),
+            exclude_branches: configuration.exclude_branches.clone(),
+            tags: configuration.tags,
+            branch_type: configuration.branch_type.to_string(),
+            revisions: configuration.revisions.clone(),
+            pathspecs: configuration.pathspecs.clone(),
+            sessions: configuration.sessions,
+            by_repository: configuration.by_repository,
+            by_component: configuration.by_component,
+            component_depth: configuration.component_depth,
+            components: configuration.components.clone(),
+            group_by: configuration
+                .group_by
+                .map(|group_by| group_by.to_string().to_lowercase()),
+            time_zone: configuration.time_zone.to_string(),
+        }
+    }
+}
+
+pub(crate) fn parse_email_alias(s: &str) -> Result<(String, String)> {
     let mut splitter = s.splitn(2, '=');
     match splitter.next() {
         Some(a) => match splitter.next() {
@@ -45,60 +653,49 @@ fn parse_email_alias(s: &str) -> Result<(String, String)> {
 pub fn parse_arguments(args: &clap::ArgMatches) -> Result<Configuration> {
     let args_stats = args.subcommand_matches("stats").unwrap();
 
-    let max_commit_diff = args_stats
-        .value_of("max-commit-diff")
+    let git_repo_paths = args_stats
+        .values_of("REPO_PATH")
         .unwrap()
-        .parse::<u32>()
-        .context("Failed to parse max commit diff to u32.")?;
-    let first_commit_addition = args_stats
-        .value_of("first-commit-add")
-        .unwrap()
-        .parse::<u32>()
-        .context("Failed to parse first commit add to u32.")?;
-    let since = match args_stats.value_of("since") {
-        Some(s) => CommitTimeBound::from_str(s)?,
-        None => CommitTimeBound::Always,
-    };
-    let until = match args_stats.value_of("until") {
-        Some(s) => CommitTimeBound::from_str(s)?,
-        None => CommitTimeBound::Always,
-    };
-    let merge_requests = args_stats.is_present("merge-requests");
-    let git_repo_path = args_stats.value_of("REPO_PATH").unwrap();
-    let aliases = match args_stats.values_of("email") {
-        Some(vs) => {
-            let vec: Vec<&str> = vs.collect();
-            let results: Result<Vec<(String, String)>, anyhow::Error> =
-                vec.iter().try_fold(Vec::new(), |mut acc, e| {
-                    let alias = parse_email_alias(e)?;
-                    acc.push(alias);
-                    Ok(acc)
-                });
-            results?
-        }
-        None => Vec::new(),
-    }
-    .into_iter()
-    .collect::<HashMap<String, String>>();
-    let branch = args_stats.value_of("branch").map(|b| b.to_string());
-    let branch_type = match args_stats.value_of("branch-type") {
-        None => BranchType::Local,
-        Some("local") => BranchType::Local,
-        Some("remote") => BranchType::Remote,
-        Some(x) => return Err(anyhow!("Invalid branch type '{}'", x)),
+        .map(PathBuf::from)
+        .collect::<Vec<_>>();
+    // Every repository is estimated with one configuration, read from the first path.
+    let configuration_path = repository_configuration_path(&git_repo_paths[0]);
+    for path in git_repo_paths[1..]
+        .iter()
+        .filter_map(|path| repository_configuration_path(path))
+        .filter(|path| Some(path) != configuration_path.as_ref())
+    {
+        log::warn!(
+            "Ignoring '{}': only the repository configuration of the first path is read",
+            path.display()
+        );
+    }
+    let mut layered = LayeredConfiguration::discover(&git_repo_paths[0])?;
+    layered.layer(
+        ConfigurationValues::from_arguments(args_stats)?,
+        ConfigurationSource::CommandLine,
+    );
+
+    let configuration = layered.build(&git_repo_paths)?;
+    let output = args_stats.value_of("output").map(PathBuf::from);
+
+    // An explicit format always wins over the extension of the output file.
+    let output_format = match &output {
+        Some(path) if args_stats.occurrences_of("format") == 0 => {
+            OutputFormat::from_extension(path).unwrap_or(configuration.output_format)
+        }
+        _ => configuration.output_format,
     };
-    let output_format = value_t!(args_stats, "format", OutputFormat).unwrap();
 
     Ok(Configuration {
-        max_commit_diff: Duration::minutes(max_commit_diff.into()),
-        first_commit_addition: Duration::minutes(first_commit_addition.into()),
-        since,
-        until,
-        merge_requests,
-        git_repo_path: PathBuf::from(git_repo_path),
-        email_aliases: aliases,
-        branch,
-        branch_type,
+        scan_repositories: args_stats.is_present("scan"),
+        pathspecs: args_stats
+            .values_of("PATHSPEC")
+            .map(|vs| vs.map(String::from).collect())
+            .unwrap_or_default(),
+        output,
+        force: args_stats.is_present("force"),
         output_format,
+        ..configuration
     })
 }
@@ -0,0 +1,509 @@
+use super::statistics_configuration::{
+    parse_email_alias, BranchKind, Configuration, GroupBy, MergeCommits, OutputFormat,
+};
+use super::statistics_estimator::{DiffWeights, EstimatorKind};
+use crate::git::{CommitTimeBound, ReportTimeZone};
+use anyhow::{anyhow, Context, Result};
+use chrono::Duration;
+use git2::Repository;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Names of configuration files looked up in the repository root, in order of preference.
+pub const REPOSITORY_CONFIGURATION_FILES: [&str; 2] = [".jikyuu.toml", "jikyuu.toml"];
+
+/// Name of the configuration file looked up in the user configuration directory.
+pub const USER_CONFIGURATION_FILE: &str = "config.toml";
+
+/// Keys of every configurable value, in display order.
+pub const CONFIGURATION_KEYS: [&str; 34] = [
+    "estimator",
+    "diff-reference-lines",
+    "diff-exponent",
+    "diff-max-factor",
+    "diff-minutes-per-line",
+    "max-commit-diff",
+    "first-commit-add",
+    "legacy-first-session",
+    "since",
+    "until",
+    "merges",
+    "first-parent",
+    "recurse-submodules",
+    "email",
+    "mailmap",
+    "author",
+    "exclude-author",
+    "bots",
+    "include-bots",
+    "branch",
+    "exclude-branch",
+    "branch-type",
+    "tags",
+    "revision",
+    "format",
+    "totals",
+    "sessions",
+    "by-repository",
+    "by-component",
+    "component-depth",
+    "components",
+    "group-by",
+    "time-zone",
+    "json-legacy",
+];
+
+/// Where a configuration value came from.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConfigurationSource {
+    Default,
+    User(PathBuf),
+    Repository(PathBuf),
+    CommandLine,
+}
+
+impl fmt::Display for ConfigurationSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Default => write!(f, "default"),
+            Self::User(path) => write!(f, "user ({})", path.display()),
+            Self::Repository(path) => write!(f, "repository ({})", path.display()),
+            Self::CommandLine => write!(f, "command line"),
+        }
+    }
+}
+
+/// A value given either as a single string or as a list of strings.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+pub enum StringList {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl StringList {
+    pub fn to_vec(&self) -> Vec<String> {
+        match self {
+            Self::One(s) => vec![s.clone()],
+            Self::Many(v) => v.clone(),
+        }
+    }
+}
+
+/// Configuration values of a single source, as written in a configuration file.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct ConfigurationValues {
+    pub estimator: Option<String>,
+    pub diff_reference_lines: Option<usize>,
+    pub diff_exponent: Option<f64>,
+    pub diff_max_factor: Option<f64>,
+    pub diff_minutes_per_line: Option<f64>,
+    pub max_commit_diff: Option<u32>,
+    pub first_commit_add: Option<u32>,
+    pub legacy_first_session: Option<bool>,
+    pub since: Option<String>,
+    pub until: Option<String>,
+    pub merges: Option<String>,
+    /// Deprecated alias of `merges = "include"`.
+    pub merge_requests: Option<bool>,
+    pub first_parent: Option<bool>,
+    pub recurse_submodules: Option<bool>,
+    pub email: Option<HashMap<String, String>>,
+    pub mailmap: Option<bool>,
+    pub author: Option<StringList>,
+    pub exclude_author: Option<StringList>,
+    pub bots: Option<StringList>,
+    pub include_bots: Option<bool>,
+    pub branch: Option<StringList>,
+    pub exclude_branch: Option<StringList>,
+    pub branch_type: Option<String>,
+    pub tags: Option<bool>,
+    pub revision: Option<StringList>,
+    pub format: Option<String>,
+    pub totals: Option<bool>,
+    pub sessions: Option<bool>,
+    pub by_repository: Option<bool>,
+    pub by_component: Option<bool>,
+    pub component_depth: Option<usize>,
+    pub components: Option<BTreeMap<String, StringList>>,
+    pub group_by: Option<String>,
+    pub time_zone: Option<String>,
+    pub json_legacy: Option<bool>,
+}
+
+impl ConfigurationValues {
+    /// Read configuration values from a TOML file.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read configuration file {:?}.", path))?;
+        toml::from_str(&contents)
+            .map_err(|e| anyhow!("Failed to parse configuration file {:?}: {}", path, e))
+    }
+
+    /// Read the configuration values explicitly given on the command line.
+    ///
+    /// Arguments that only hold their default value are left unset so that they do not override
+    /// configuration files.
+    pub fn from_arguments(args: &clap::ArgMatches) -> Result<Self> {
+        let explicit = |name: &str| args.occurrences_of(name) > 0;
+        let value = |name: &str| {
+            if explicit(name) {
+                args.value_of(name).map(|v| v.to_string())
+            } else {
+                None
+            }
+        };
+        let flag = |name: &str| if explicit(name) { Some(true) } else { None };
+        // A flag and its negation both override configuration files, e.g. `sessions = true` is
+        // turned off with `--no-sessions`.
+        let switch =
+            |name: &str, negation: &str| flag(name).or_else(|| flag(negation).map(|_| false));
+        let values = |name: &str| {
+            args.values_of(name)
+                .map(|vs| StringList::Many(vs.map(String::from).collect()))
+        };
+        let minutes = |name: &str| -> Result<Option<u32>> {
+            match value(name) {
+                Some(v) => Ok(Some(v.parse::<u32>().with_context(|| {
+                    format!("Failed to parse {} to u32.", name.replace('-', " "))
+                })?)),
+                None => Ok(None),
+            }
+        };
+
+        let coefficient = |name: &str| -> Result<Option<f64>> {
+            match value(name) {
+                Some(v) => Ok(Some(v.parse::<f64>().with_context(|| {
+                    format!("Failed to parse {} to a number.", name.replace('-', " "))
+                })?)),
+                None => Ok(None),
+            }
+        };
+
+        let email = match args.values_of("email") {
+            Some(vs) => Some(
+                vs.map(parse_email_alias)
+                    .collect::<Result<HashMap<String, String>>>()?,
+            ),
+            None => None,
+        };
+
+        Ok(Self {
+            estimator: value("estimator"),
+            diff_reference_lines: match value("diff-reference-lines") {
+                Some(v) => Some(
+                    v.parse::<usize>()
+                        .context("Failed to parse diff reference lines to usize.")?,
+                ),
+                None => None,
+            },
+            diff_exponent: coefficient("diff-exponent")?,
+            diff_max_factor: coefficient("diff-max-factor")?,
+            diff_minutes_per_line: coefficient("diff-minutes-per-line")?,
+            max_commit_diff: minutes("max-commit-diff")?,
+            first_commit_add: minutes("first-commit-add")?,
+            legacy_first_session: switch("legacy-first-session", "no-legacy-first-session"),
+            since: value("since"),
+            until: value("until"),
+            merges: value("merges").or_else(|| flag("merge-requests").map(|_| "include".into())),
+            merge_requests: None,
+            first_parent: switch("first-parent", "no-first-parent"),
+            recurse_submodules: switch("recurse-submodules", "no-recurse-submodules"),
+            email,
+            mailmap: switch("mailmap", "no-mailmap"),
+            author: values("author"),
+            exclude_author: values("exclude-author"),
+            bots: None,
+            include_bots: switch("include-bots", "exclude-bots"),
+            branch: values("branch"),
+            exclude_branch: values("exclude-branch"),
+            branch_type: value("branch-type"),
+            tags: switch("tags", "no-tags"),
+            revision: values("revision"),
+            format: value("format"),
+            totals: switch("totals", "no-totals"),
+            sessions: switch("sessions", "no-sessions"),
+            by_repository: switch("by-repository", "no-by-repository"),
+            by_component: switch("by-component", "no-by-component"),
+            component_depth: match value("component-depth") {
+                Some(v) => Some(
+                    v.parse::<usize>()
+                        .context("Failed to parse component depth to usize.")?,
+                ),
+                None => None,
+            },
+            components: None,
+            group_by: value("group-by"),
+            time_zone: value("time-zone"),
+            json_legacy: switch("json-legacy", "no-json-legacy"),
+        })
+    }
+}
+
+/// Configuration values merged from several sources, later sources taking precedence.
+#[derive(Clone, Debug, Default)]
+pub struct LayeredConfiguration {
+    values: ConfigurationValues,
+    sources: BTreeMap<&'static str, ConfigurationSource>,
+}
+
+macro_rules! layer_values {
+    ($self:ident, $values:ident, $source:ident, $($field:ident => $key:expr),* $(,)?) => {
+        $(
+            if $values.$field.is_some() {
+                $self.values.$field = $values.$field;
+                $self.sources.insert($key, $source.clone());
+            }
+        )*
+    };
+}
+
+impl LayeredConfiguration {
+    /// Discover the user and repository configuration files for a repository path.
+    pub fn discover(git_repo_path: &Path) -> Result<Self> {
+        let mut layered = Self::default();
+
+        if let Some(path) = user_configuration_path() {
+            if path.is_file() {
+                let values = ConfigurationValues::from_file(&path)?;
+                layered.layer(values, ConfigurationSource::User(path));
+            }
+        }
+
+        if let Some(path) = repository_configuration_path(git_repo_path) {
+            let values = ConfigurationValues::from_file(&path)?;
+            layered.layer(values, ConfigurationSource::Repository(path));
+        }
+
+        Ok(layered)
+    }
+
+    /// Merge values on top of the current values.
+    pub fn layer(&mut self, mut values: ConfigurationValues, source: ConfigurationSource) {
+        if let Some(merge_requests) = values.merge_requests.take() {
+            let merges = if merge_requests { "include" } else { "exclude" };
+            values.merges.get_or_insert_with(|| merges.into());
+        }
+
+        if let Some(email) = values.email {
+            self.values
+                .email
+                .get_or_insert_with(HashMap::new)
+                .extend(email);
+            self.sources.insert("email", source.clone());
+        }
+
+        // Revisions replace the branches selected by earlier sources, and branches the revisions.
+        if values.revision.is_some() {
+            self.values.branch = None;
+            self.values.exclude_branch = None;
+            self.values.branch_type = None;
+            self.values.tags = None;
+            for key in ["branch", "exclude-branch", "branch-type", "tags"].iter() {
+                self.sources.remove(key);
+            }
+        } else if values.branch.is_some() {
+            self.values.revision = None;
+            self.sources.remove("revision");
+        }
+
+        layer_values!(self, values, source,
+            estimator => "estimator",
+            diff_reference_lines => "diff-reference-lines",
+            diff_exponent => "diff-exponent",
+            diff_max_factor => "diff-max-factor",
+            diff_minutes_per_line => "diff-minutes-per-line",
+            max_commit_diff => "max-commit-diff",
+            first_commit_add => "first-commit-add",
+            legacy_first_session => "legacy-first-session",
+            since => "since",
+            until => "until",
+            merges => "merges",
+            first_parent => "first-parent",
+            recurse_submodules => "recurse-submodules",
+            mailmap => "mailmap",
+            author => "author",
+            exclude_author => "exclude-author",
+            bots => "bots",
+            include_bots => "include-bots",
+            branch => "branch",
+            exclude_branch => "exclude-branch",
+            branch_type => "branch-type",
+            tags => "tags",
+            revision => "revision",
+            format => "format",
+            totals => "totals",
+            sessions => "sessions",
+            by_repository => "by-repository",
+            by_component => "by-component",
+            component_depth => "component-depth",
+            components => "components",
+            group_by => "group-by",
+            time_zone => "time-zone",
+            json_legacy => "json-legacy",
+        );
+    }
+
+    /// Source of the value for a configuration key.
+    pub fn source(&self, key: &str) -> ConfigurationSource {
+        self.sources
+            .get(key)
+            .cloned()
+            .unwrap_or(ConfigurationSource::Default)
+    }
+
+    /// Build and validate the effective configuration.
+    pub fn build(&self, git_repo_paths: &[PathBuf]) -> Result<Configuration> {
+        let values = &self.values;
+        let default = Configuration::default();
+
+        let estimator = match &values.estimator {
+            Some(s) => EstimatorKind::from_str(s)?,
+            None => default.estimator,
+        };
+        let diff_weights = DiffWeights {
+            reference_lines: values
+                .diff_reference_lines
+                .unwrap_or(default.diff_weights.reference_lines),
+            exponent: values
+                .diff_exponent
+                .unwrap_or(default.diff_weights.exponent),
+            max_factor: values
+                .diff_max_factor
+                .unwrap_or(default.diff_weights.max_factor),
+            minutes_per_line: values
+                .diff_minutes_per_line
+                .unwrap_or(default.diff_weights.minutes_per_line),
+        };
+        let since = match &values.since {
+            Some(s) => CommitTimeBound::from_str(s)?,
+            None => default.since,
+        };
+        let until = match &values.until {
+            Some(s) => CommitTimeBound::from_str(s)?,
+            None => default.until,
+        };
+        let branch_type = match &values.branch_type {
+            Some(s) => BranchKind::from_str(s)?,
+            None => default.branch_type,
+        };
+        let output_format = match &values.format {
+            Some(s) => OutputFormat::from_str(s).map_err(|e| anyhow!(e))?,
+            None => default.output_format,
+        };
+        let merges = match &values.merges {
+            Some(s) => MergeCommits::from_str(s)?,
+            None => default.merges,
+        };
+        let group_by = match &values.group_by {
+            Some(s) => Some(GroupBy::from_str(s).map_err(|e| anyhow!(e))?),
+            None => default.group_by,
+        };
+        let time_zone = match &values.time_zone {
+            Some(s) => ReportTimeZone::from_str(s)?,
+            None => default.time_zone,
+        };
+
+        let builder = values.email.iter().flatten().fold(
+            Configuration::builder()
+                .estimator(estimator)
+                .diff_weights(diff_weights)
+                .max_commit_diff(match values.max_commit_diff {
+                    Some(minutes) => Duration::minutes(minutes.into()),
+                    None => default.max_commit_diff,
+                })
+                .first_commit_addition(match values.first_commit_add {
+                    Some(minutes) => Duration::minutes(minutes.into()),
+                    None => default.first_commit_addition,
+                })
+                .legacy_first_session(
+                    values
+                        .legacy_first_session
+                        .unwrap_or(default.legacy_first_session),
+                )
+                .since(since)
+                .until(until)
+                .merges(merges)
+                .first_parent(values.first_parent.unwrap_or(default.first_parent))
+                .git_repo_paths(git_repo_paths.iter().cloned())
+                .recurse_submodules(
+                    values
+                        .recurse_submodules
+                        .unwrap_or(default.recurse_submodules),
+                )
+                .mailmap(values.mailmap.unwrap_or(default.mailmap))
+                .authors(match &values.author {
+                    Some(authors) => authors.to_vec(),
+                    None => default.authors,
+                })
+                .exclude_authors(match &values.exclude_author {
+                    Some(authors) => authors.to_vec(),
+                    None => default.exclude_authors,
+                })
+                .bots(match &values.bots {
+                    Some(bots) => bots.to_vec(),
+                    None => default.bots,
+                })
+                .include_bots(values.include_bots.unwrap_or(default.include_bots))
+                .branches(match &values.branch {
+                    Some(branches) => branches.to_vec(),
+                    None => default.branches,
+                })
+                .exclude_branches(match &values.exclude_branch {
+                    Some(branches) => branches.to_vec(),
+                    None => default.exclude_branches,
+                })
+                .branch_type(branch_type)
+                .tags(values.tags.unwrap_or(default.tags))
+                .revisions(match &values.revision {
+                    Some(revisions) => revisions.to_vec(),
+                    None => default.revisions,
+                })
+                .output_format(output_format)
+                .totals(values.totals.unwrap_or(default.totals))
+                .sessions(values.sessions.unwrap_or(default.sessions))
+                .by_repository(values.by_repository.unwrap_or(default.by_repository))
+                .by_component(values.by_component.unwrap_or(default.by_component))
+                .component_depth(values.component_depth.unwrap_or(default.component_depth))
+                .components(match &values.components {
+                    Some(components) => components
+                        .iter()
+                        .map(|(name, patterns)| (name.clone(), patterns.to_vec()))
+                        .collect(),
+                    None => default.components,
+                })
+                .group_by(group_by)
+                .time_zone(time_zone)
+                .json_legacy(values.json_legacy.unwrap_or(default.json_legacy)),
+            |builder, (other, main)| builder.email_alias(other.clone(), main.clone()),
+        );
+
+        Ok(builder.build()?)
+    }
+}
+
+/// Path of the user configuration file, `$XDG_CONFIG_HOME/jikyuu/config.toml`.
+pub fn user_configuration_path() -> Option<PathBuf> {
+    let config_home = match env::var_os("XDG_CONFIG_HOME") {
+        Some(path) if !path.is_empty() => PathBuf::from(path),
+        _ => PathBuf::from(env::var_os("HOME")?).join(".config"),
+    };
+
+    Some(config_home.join("jikyuu").join(USER_CONFIGURATION_FILE))
+}
+
+/// Path of the configuration file in the root of the repository containing `git_repo_path`.
+pub fn repository_configuration_path(git_repo_path: &Path) -> Option<PathBuf> {
+    let repository = Repository::discover(git_repo_path).ok()?;
+    let root = repository.workdir()?;
+
+    REPOSITORY_CONFIGURATION_FILES
+        .iter()
+        .map(|name| root.join(name))
+        .find(|path| path.is_file())
+}
@@ -0,0 +1,260 @@
+use super::statistics::RepositoryCommit;
+use super::statistics_configuration::Configuration;
+use anyhow::anyhow;
+use chrono::Duration;
+use std::fmt;
+use std::str::FromStr;
+
+/// Time an estimator attributes to a commit, i.e. the work leading up to it.
+#[derive(Debug)]
+pub struct CommitEstimate<'a, 'repo> {
+    pub commit: &'a RepositoryCommit<'repo>,
+    pub duration: Duration,
+}
+
+/// Consecutive commits of an author estimated to be one work session.
+#[derive(Debug)]
+pub struct SessionEstimate<'a, 'repo> {
+    pub commits: Vec<CommitEstimate<'a, 'repo>>,
+}
+
+impl SessionEstimate<'_, '_> {
+    /// Time spent on the session.
+    pub fn duration(&self) -> Duration {
+        self.commits
+            .iter()
+            .fold(Duration::zero(), |acc, commit| acc + commit.duration)
+    }
+}
+
+/// Work sessions estimated from the commits of an author.
+#[derive(Debug, Default)]
+pub struct AuthorEstimate<'a, 'repo> {
+    pub sessions: Vec<SessionEstimate<'a, 'repo>>,
+}
+
+impl AuthorEstimate<'_, '_> {
+    /// Time spent on every session.
+    pub fn duration(&self) -> Duration {
+        self.sessions
+            .iter()
+            .fold(Duration::zero(), |acc, session| acc + session.duration())
+    }
+}
+
+/// Model of the time an author spent working, estimated from their commits.
+pub trait Estimator {
+    /// Group the commits of an author, sorted by time, into work sessions and estimate the time
+    /// spent on each commit.
+    ///
+    /// Every commit belongs to exactly one session and sessions are never empty.
+    fn estimate<'a, 'repo>(
+        &self,
+        commits: &[&'a RepositoryCommit<'repo>],
+        configuration: &Configuration,
+    ) -> AuthorEstimate<'a, 'repo>;
+
+    /// Whether the estimator reads the `line_stats` of commits, which are costly to compute.
+    fn needs_line_stats(&self) -> bool {
+        false
+    }
+}
+
+/// Group commits less than the max commit diff apart into sessions.
+///
+/// `first` estimates the first commit of a session from the first commit addition, and `gap` a
+/// later commit from the time since the previous commit.
+fn estimate_sessions<'a, 'repo>(
+    commits: &[&'a RepositoryCommit<'repo>],
+    configuration: &Configuration,
+    first: impl Fn(&RepositoryCommit, Duration) -> Duration,
+    gap: impl Fn(&RepositoryCommit, Duration) -> Duration,
+) -> AuthorEstimate<'a, 'repo> {
+    let first_commit_addition = configuration.first_commit_addition;
+    // Earlier versions added nothing for the first commit of the first session.
+    let first_session_addition = if configuration.legacy_first_session {
+        Duration::zero()
+    } else {
+        first_commit_addition
+    };
+
+    let first_commit = match commits.first() {
+        Some(first_commit) => first_commit,
+        None => return AuthorEstimate::default(),
+    };
+
+    let mut sessions = Vec::new();
+    let mut session = vec![CommitEstimate {
+        commit: first_commit,
+        duration: first(first_commit, first_session_addition),
+    }];
+    for pair in commits.windows(2) {
+        let (commit, next_commit) = (pair[0], pair[1]);
+        let diff_seconds = next_commit.commit.time().seconds() - commit.commit.time().seconds();
+        let dur = Duration::seconds(diff_seconds);
+
+        if dur < configuration.max_commit_diff {
+            session.push(CommitEstimate {
+                commit: next_commit,
+                duration: gap(next_commit, dur),
+            });
+        } else {
+            sessions.push(SessionEstimate { commits: session });
+            session = vec![CommitEstimate {
+                commit: next_commit,
+                duration: first(next_commit, first_commit_addition),
+            }];
+        }
+    }
+    sessions.push(SessionEstimate { commits: session });
+
+    AuthorEstimate { sessions }
+}
+
+/// The git-hours heuristic.
+///
+/// Commits less than the max commit diff apart belong to one session and the time between them is
+/// spent on the later commit. The first commit of every session adds the first commit addition.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GitHours;
+
+impl Estimator for GitHours {
+    fn estimate<'a, 'repo>(
+        &self,
+        commits: &[&'a RepositoryCommit<'repo>],
+        configuration: &Configuration,
+    ) -> AuthorEstimate<'a, 'repo> {
+        estimate_sessions(commits, configuration, |_, addition| addition, |_, gap| gap)
+    }
+}
+
+/// Coefficients of the `DiffWeighted` estimator.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DiffWeights {
+    /// Lines changed by a commit that adds exactly the first commit addition.
+    pub reference_lines: usize,
+    /// Exponent of the growth of the first commit addition with the lines changed, `1.0` being
+    /// linear.
+    pub exponent: f64,
+    /// Largest multiple of the first commit addition a commit adds.
+    pub max_factor: f64,
+    /// Minutes per line changed that the time since the previous commit is capped at.
+    pub minutes_per_line: f64,
+}
+
+impl Default for DiffWeights {
+    fn default() -> Self {
+        Self {
+            reference_lines: 100,
+            exponent: 0.5,
+            max_factor: 4.0,
+            minutes_per_line: 5.0,
+        }
+    }
+}
+
+impl DiffWeights {
+    /// First commit addition of a commit changing `lines`.
+    ///
+    /// Commits changing no lines, e.g. empty, binary-only or rename-only commits, are weighted like
+    /// a one-line change.
+    pub fn first_commit_addition(&self, addition: Duration, lines: usize) -> Duration {
+        let factor = (lines.max(1) as f64 / self.reference_lines as f64)
+            .powf(self.exponent)
+            .min(self.max_factor);
+
+        Duration::seconds((addition.num_seconds() as f64 * factor).round() as i64)
+    }
+
+    /// Time since the previous commit spent on a commit changing `lines`, at least one line.
+    pub fn gap(&self, gap: Duration, lines: usize) -> Duration {
+        let cap =
+            Duration::seconds((lines.max(1) as f64 * self.minutes_per_line * 60.0).round() as i64);
+
+        gap.min(cap)
+    }
+}
+
+/// The git-hours heuristic weighted by the lines each commit changed.
+///
+/// The first commit addition grows with the lines changed, so a typo fix adds less than a large
+/// feature, and the time since the previous commit is capped for commits changing few lines.
+/// Commits without line stats are estimated like `GitHours` does.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DiffWeighted;
+
+impl Estimator for DiffWeighted {
+    fn estimate<'a, 'repo>(
+        &self,
+        commits: &[&'a RepositoryCommit<'repo>],
+        configuration: &Configuration,
+    ) -> AuthorEstimate<'a, 'repo> {
+        let weights = &configuration.diff_weights;
+
+        estimate_sessions(
+            commits,
+            configuration,
+            |commit, addition| match commit.line_stats {
+                Some(stats) => weights.first_commit_addition(addition, stats.lines()),
+                None => addition,
+            },
+            |commit, gap| match commit.line_stats {
+                Some(stats) => weights.gap(gap, stats.lines()),
+                None => gap,
+            },
+        )
+    }
+
+    fn needs_line_stats(&self) -> bool {
+        true
+    }
+}
+
+/// Estimators selectable by name.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum EstimatorKind {
+    /// The git-hours heuristic, see `GitHours`.
+    GitHours,
+    /// The git-hours heuristic weighted by lines changed, see `DiffWeighted`.
+    DiffWeighted,
+}
+
+impl EstimatorKind {
+    pub fn variants() -> [&'static str; 2] {
+        ["git-hours", "diff-weighted"]
+    }
+
+    /// Estimator of this kind.
+    pub fn estimator(&self) -> Box<dyn Estimator> {
+        match self {
+            Self::GitHours => Box::new(GitHours),
+            Self::DiffWeighted => Box::new(DiffWeighted),
+        }
+    }
+}
+
+impl FromStr for EstimatorKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "git-hours" => Ok(Self::GitHours),
+            "diff-weighted" => Ok(Self::DiffWeighted),
+            _ => Err(anyhow!(
+                "Invalid estimator '{}', expected one of {}",
+                s,
+                Self::variants().join(", ")
+            )),
+        }
+    }
+}
+
+impl fmt::Display for EstimatorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::GitHours => "git-hours",
+            Self::DiffWeighted => "diff-weighted",
+        };
+        write!(f, "{}", name)
+    }
+}
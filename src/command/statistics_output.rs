@@ -0,0 +1,118 @@
+use super::statistics_configuration::Configuration;
+use anyhow::{anyhow, Context, Result};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Stdout, Write};
+use std::path::{Path, PathBuf};
+
+/// Destination of a report.
+///
+/// Reports never go through the logger, so log levels and log formats cannot alter them.
+pub enum ReportWriter {
+    Stdout(Stdout),
+    /// Report written to a temporary file next to `path`, renamed into place when finished.
+    File {
+        path: PathBuf,
+        temporary_path: PathBuf,
+        writer: BufWriter<File>,
+    },
+}
+
+/// Path of the temporary file a report to `path` is written to before it is renamed into place.
+///
+/// The temporary file is in the same directory so the rename does not cross file systems.
+fn temporary_path(path: &Path) -> PathBuf {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    path.with_file_name(format!(".{}.{}.tmp", file_name, std::process::id()))
+}
+
+impl ReportWriter {
+    /// Write the report to standard output.
+    pub fn stdout() -> Self {
+        Self::Stdout(io::stdout())
+    }
+
+    /// Write the report to the file at `path`, replacing it only when `force` is set.
+    ///
+    /// The file is only replaced once the report is finished, so a failed run never leaves a
+    /// partial report behind.
+    pub fn create(path: &Path, force: bool) -> Result<Self> {
+        if path.is_dir() {
+            return Err(anyhow!("Output path {:?} is a directory.", path));
+        }
+        if path.exists() && !force {
+            return Err(anyhow!(
+                "Output file {:?} already exists. Pass --force to overwrite it.",
+                path
+            ));
+        }
+
+        let temporary_path = temporary_path(path);
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&temporary_path)
+            .with_context(|| format!("Failed to create output file {:?}.", temporary_path))?;
+
+        Ok(Self::File {
+            path: path.to_path_buf(),
+            temporary_path,
+            writer: BufWriter::new(file),
+        })
+    }
+
+    /// Open the report destination of the configuration.
+    pub fn from_configuration(configuration: &Configuration) -> Result<Self> {
+        match &configuration.output {
+            Some(path) => Self::create(path, configuration.force),
+            None => Ok(Self::stdout()),
+        }
+    }
+
+    /// Flush the report and move it into place.
+    pub fn finish(mut self) -> Result<()> {
+        match &mut self {
+            Self::Stdout(stdout) => stdout.flush().context("Failed to write report to stdout."),
+            Self::File {
+                path,
+                temporary_path,
+                writer,
+            } => {
+                writer
+                    .flush()
+                    .and_then(|_| writer.get_ref().sync_all())
+                    .with_context(|| format!("Failed to write output file {:?}.", path))?;
+                fs::rename(temporary_path, &path)
+                    .with_context(|| format!("Failed to move report into place at {:?}.", path))
+            }
+        }
+    }
+}
+
+impl Drop for ReportWriter {
+    fn drop(&mut self) {
+        // Nothing is left to remove once the report was renamed into place.
+        if let Self::File { temporary_path, .. } = self {
+            let _ = fs::remove_file(temporary_path);
+        }
+    }
+}
+
+impl Write for ReportWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Stdout(stdout) => stdout.write(buf),
+            Self::File { writer, .. } => writer.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Stdout(stdout) => stdout.flush(),
+            Self::File { writer, .. } => writer.flush(),
+        }
+    }
+}
@@ -0,0 +1,103 @@
+#![allow(deprecated)]
+
+use super::statistics_configuration::GroupBy;
+use crate::git::{CommitHours, PeriodHours, ReportTimeZone, WorkSession};
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use std::collections::BTreeMap;
+
+/// ISO key of the period a local date time falls into.
+pub fn period_key(date_time: &NaiveDateTime, group_by: GroupBy) -> String {
+    match group_by {
+        GroupBy::Day => date_time.format("%Y-%m-%d").to_string(),
+        GroupBy::Week => date_time.format("%G-W%V").to_string(),
+        GroupBy::Month => date_time.format("%Y-%m").to_string(),
+        GroupBy::Year => date_time.format("%Y").to_string(),
+    }
+}
+
+/// Start of the period following the one a local date time falls into.
+fn next_period_start(date_time: &NaiveDateTime, group_by: GroupBy) -> NaiveDateTime {
+    let date = date_time.date();
+    let next = match group_by {
+        GroupBy::Day => date + Duration::days(1),
+        GroupBy::Week => date + Duration::days(7 - date.weekday().num_days_from_monday() as i64),
+        GroupBy::Month => match date.month() {
+            12 => NaiveDate::from_ymd(date.year() + 1, 1, 1),
+            m => NaiveDate::from_ymd(date.year(), m + 1, 1),
+        },
+        GroupBy::Year => NaiveDate::from_ymd(date.year() + 1, 1, 1),
+    };
+
+    NaiveDateTime::new(next, NaiveTime::from_hms(0, 0, 0))
+}
+
+/// Split the estimated time of a session across the periods it spans.
+///
+/// The time of a commit is spread over the time since the previous commit of the session, up to
+/// the commit. Whatever does not fit, like the first commit addition, is counted in the period the
+/// commit falls into. `add` receives the repository of the commit, the period key and the time.
+fn split_session(
+    session: &WorkSession,
+    group_by: GroupBy,
+    time_zone: ReportTimeZone,
+    add: &mut impl FnMut(&str, String, Duration),
+) {
+    let mut previous = None;
+    for commit in session.commit_durations.iter() {
+        let end = time_zone.to_naive_date_time(&commit.time);
+        let mut current = match previous {
+            Some(previous) => std::cmp::max(previous, end - commit.duration),
+            None => end,
+        };
+        let mut remaining = commit.duration;
+
+        loop {
+            let key = period_key(&current, group_by);
+            let next = next_period_start(&current, group_by);
+            if next >= end {
+                // The last piece takes whatever is left so the periods add up to the commit.
+                add(&commit.repository, key, remaining);
+                break;
+            }
+            let piece = next - current;
+            add(&commit.repository, key, piece);
+            remaining -= piece;
+            current = next;
+        }
+        previous = Some(end);
+    }
+}
+
+/// Bucket the estimated time of every author into calendar periods.
+pub fn group_by_period(
+    times: &[CommitHours],
+    group_by: GroupBy,
+    time_zone: ReportTimeZone,
+) -> Vec<PeriodHours> {
+    times
+        .iter()
+        .map(|time| {
+            let mut periods = BTreeMap::new();
+            let mut repositories: BTreeMap<String, BTreeMap<String, Duration>> = BTreeMap::new();
+            let mut add = |repository: &str, key: String, duration: Duration| {
+                *repositories
+                    .entry(repository.to_string())
+                    .or_default()
+                    .entry(key.clone())
+                    .or_insert_with(Duration::zero) += duration;
+                *periods.entry(key).or_insert_with(Duration::zero) += duration;
+            };
+            for session in time.sessions.iter() {
+                split_session(session, group_by, time_zone, &mut add);
+            }
+
+            PeriodHours {
+                email: time.email.clone(),
+                author_name: time.author_name.clone(),
+                duration: time.duration,
+                periods,
+                repositories,
+            }
+        })
+        .collect()
+}
@@ -1,14 +1,26 @@
-use super::statistics_configuration::OutputFormat;
-use crate::git::{CommitHours, CommitHoursJson};
+use super::statistics::Estimate;
+use super::statistics_configuration::{Configuration, OutputFormat};
+use super::statistics_period::group_by_period;
+use crate::git::{
+    CommitHours, CommitHoursJson, ExcludedAuthorJson, PeriodHours, PeriodHoursJson, ReportJson,
+    RepositoryJson, TotalsJson, WorkSession, WorkSessionJson, REPORT_SCHEMA_VERSION,
+};
 use anyhow::Result;
-use prettytable::{format, row, Table};
+use chrono::{Duration, SecondsFormat, Utc};
+use prettytable::{format, Cell, Row, Table};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+
+fn to_hours(duration: &Duration) -> f32 {
+    (duration.num_minutes() as f32) / 60.0
+}
 
 fn get_totals(times: &[CommitHours]) -> (f32, usize) {
     let mut total_estimated_hours = 0.0;
     let mut total_commits = 0;
     for time in times.iter() {
         let commits = time.commit_count;
-        let estimated_hours = (time.duration.num_minutes() as f32) / 60.0;
+        let estimated_hours = to_hours(&time.duration);
         total_commits += commits;
         total_estimated_hours += estimated_hours;
     }
@@ -16,7 +28,302 @@ fn get_totals(times: &[CommitHours]) -> (f32, usize) {
     (total_estimated_hours, total_commits)
 }
 
-fn print_results_stdout(times: &[CommitHours]) -> Result<()> {
+fn author_cells(author_name: &Option<String>, email: &Option<String>) -> Vec<String> {
+    let author = match author_name {
+        Some(n) => n,
+        None => "",
+    };
+    let email = match email {
+        Some(email) => email,
+        None => "(none)",
+    };
+
+    vec![author.to_string(), email.to_string()]
+}
+
+/// Tabular report shape shared by every output format but JSON.
+#[derive(Debug)]
+struct ReportTable {
+    titles: Vec<String>,
+    rows: Vec<Vec<String>>,
+    totals: Vec<String>,
+}
+
+/// Report of the estimated time by author, optionally broken down by repository.
+fn authors_table(times: &[CommitHours], by_repository: bool) -> ReportTable {
+    let mut titles = vec!["Author", "Email"];
+    if by_repository {
+        titles.push("Repository");
+    }
+    titles.extend(["Commits", "Estimated Hours"]);
+
+    let mut rows = Vec::new();
+    for time in times.iter() {
+        let author = author_cells(&time.author_name, &time.email);
+        if by_repository {
+            for (repository, repository_time) in time.repositories.iter() {
+                let mut row = author.clone();
+                row.push(repository.clone());
+                row.push(repository_time.commit_count.to_string());
+                row.push(to_hours(&repository_time.duration).to_string());
+                rows.push(row);
+            }
+        } else {
+            let mut row = author;
+            row.push(time.commit_count.to_string());
+            row.push(to_hours(&time.duration).to_string());
+            rows.push(row);
+        }
+    }
+
+    let (total_estimated_hours, total_commits) = get_totals(times);
+    let mut totals = vec![String::from("Total"), String::new()];
+    if by_repository {
+        totals.push(String::new());
+    }
+    totals.push(total_commits.to_string());
+    totals.push(total_estimated_hours.to_string());
+
+    ReportTable {
+        titles: titles.into_iter().map(String::from).collect(),
+        rows,
+        totals,
+    }
+}
+
+/// Report of every work session, optionally broken down by repository.
+fn sessions_table(times: &[CommitHours], by_repository: bool) -> ReportTable {
+    let mut titles = vec!["Author", "Email", "Start", "End"];
+    if by_repository {
+        titles.push("Repository");
+    }
+    titles.extend(["Commits", "First Commit Hours", "Estimated Hours"]);
+
+    let mut rows = Vec::new();
+    let mut total_first_commit_hours = 0.0;
+    for time in times.iter() {
+        for session in time.sessions.iter() {
+            let first_commit_hours = to_hours(&session.first_commit_addition);
+            total_first_commit_hours += first_commit_hours;
+
+            let mut session_row = author_cells(&session.author_name, &session.email);
+            session_row.extend([session.start.to_rfc3339(), session.end.to_rfc3339()]);
+            if by_repository {
+                // The first commit addition is spent in the repository of the first commit.
+                let first_repository = session
+                    .commit_durations
+                    .first()
+                    .map(|commit| commit.repository.as_str());
+                for (repository, repository_time) in session.repository_hours().iter() {
+                    let first_commit_hours = if Some(repository.as_str()) == first_repository {
+                        first_commit_hours
+                    } else {
+                        0.0
+                    };
+                    let mut row = session_row.clone();
+                    row.extend([
+                        repository.clone(),
+                        repository_time.commit_count.to_string(),
+                        first_commit_hours.to_string(),
+                        to_hours(&repository_time.duration).to_string(),
+                    ]);
+                    rows.push(row);
+                }
+            } else {
+                let mut row = session_row;
+                row.extend([
+                    session.commits.len().to_string(),
+                    first_commit_hours.to_string(),
+                    to_hours(&session.estimated_duration).to_string(),
+                ]);
+                rows.push(row);
+            }
+        }
+    }
+
+    let (total_estimated_hours, total_commits) = get_totals(times);
+    let mut totals = vec![
+        String::from("Total"),
+        String::new(),
+        String::new(),
+        String::new(),
+    ];
+    if by_repository {
+        totals.push(String::new());
+    }
+    totals.extend([
+        total_commits.to_string(),
+        total_first_commit_hours.to_string(),
+        total_estimated_hours.to_string(),
+    ]);
+
+    ReportTable {
+        titles: titles.into_iter().map(String::from).collect(),
+        rows,
+        totals,
+    }
+}
+
+/// Report of the bots left out of the estimate.
+fn bots_table(bots: &[CommitHours]) -> ReportTable {
+    let titles = ["Author", "Email", "Commits"];
+
+    let mut rows = Vec::new();
+    for bot in bots.iter() {
+        let mut row = author_cells(&bot.author_name, &bot.email);
+        row.push(bot.commit_count.to_string());
+        rows.push(row);
+    }
+
+    let (_, total_commits) = get_totals(bots);
+    let totals = vec![
+        String::from("Total"),
+        String::new(),
+        total_commits.to_string(),
+    ];
+
+    ReportTable {
+        titles: titles.iter().map(|t| t.to_string()).collect(),
+        rows,
+        totals,
+    }
+}
+
+/// Estimated time of an author broken down by key, with the author's total.
+struct BreakdownRow<'a> {
+    author_name: &'a Option<String>,
+    email: &'a Option<String>,
+    /// Repository the row is limited to, when broken down by repository.
+    repository: Option<&'a str>,
+    breakdown: &'a BTreeMap<String, Duration>,
+    duration: Duration,
+}
+
+/// Report of the estimated time by author, with one column per breakdown key.
+fn breakdown_table(breakdowns: &[BreakdownRow], by_repository: bool) -> ReportTable {
+    let keys = breakdowns
+        .iter()
+        .flat_map(|row| row.breakdown.keys().cloned())
+        .collect::<BTreeSet<_>>();
+
+    let mut titles = vec![String::from("Author"), String::from("Email")];
+    if by_repository {
+        titles.push(String::from("Repository"));
+    }
+    titles.extend(keys.iter().cloned());
+    titles.push(String::from("Total"));
+
+    let mut rows = Vec::new();
+    for breakdown in breakdowns.iter() {
+        let mut row = author_cells(breakdown.author_name, breakdown.email);
+        if by_repository {
+            row.push(breakdown.repository.unwrap_or_default().to_string());
+        }
+        row.extend(keys.iter().map(|key| match breakdown.breakdown.get(key) {
+            Some(duration) => to_hours(duration).to_string(),
+            None => String::new(),
+        }));
+        row.push(to_hours(&breakdown.duration).to_string());
+        rows.push(row);
+    }
+
+    let mut totals = vec![String::from("Total"), String::new()];
+    if by_repository {
+        totals.push(String::new());
+    }
+    totals.extend(keys.iter().map(|key| {
+        let total = breakdowns
+            .iter()
+            .filter_map(|row| row.breakdown.get(key))
+            .fold(Duration::zero(), |acc, duration| acc + *duration);
+        to_hours(&total).to_string()
+    }));
+    let total = breakdowns
+        .iter()
+        .fold(Duration::zero(), |acc, row| acc + row.duration);
+    totals.push(to_hours(&total).to_string());
+
+    ReportTable {
+        titles,
+        rows,
+        totals,
+    }
+}
+
+/// Report of the estimated time by author and calendar period, optionally broken down by
+/// repository.
+fn periods_table(periods: &[PeriodHours], by_repository: bool) -> ReportTable {
+    let mut breakdowns = Vec::new();
+    for time in periods.iter() {
+        if by_repository {
+            for (repository, repository_periods) in time.repositories.iter() {
+                breakdowns.push(BreakdownRow {
+                    author_name: &time.author_name,
+                    email: &time.email,
+                    repository: Some(repository),
+                    breakdown: repository_periods,
+                    duration: repository_periods
+                        .values()
+                        .fold(Duration::zero(), |acc, duration| acc + *duration),
+                });
+            }
+        } else {
+            breakdowns.push(BreakdownRow {
+                author_name: &time.author_name,
+                email: &time.email,
+                repository: None,
+                breakdown: &time.periods,
+                duration: time.duration,
+            });
+        }
+    }
+
+    breakdown_table(&breakdowns, by_repository)
+}
+
+/// Report of the estimated time by author and component.
+fn components_table(times: &[CommitHours]) -> ReportTable {
+    let breakdowns = times
+        .iter()
+        .map(|time| BreakdownRow {
+            author_name: &time.author_name,
+            email: &time.email,
+            repository: None,
+            breakdown: &time.components,
+            duration: time.duration,
+        })
+        .collect::<Vec<_>>();
+
+    breakdown_table(&breakdowns, false)
+}
+
+/// Hours of each key of a breakdown, summed over every author.
+fn total_hours<'a>(
+    breakdowns: impl Iterator<Item = &'a BTreeMap<String, Duration>>,
+) -> BTreeMap<String, f32> {
+    let mut totals = BTreeMap::new();
+    for breakdown in breakdowns {
+        for (key, duration) in breakdown.iter() {
+            let entry = totals.entry(key.clone()).or_insert_with(Duration::zero);
+            *entry += *duration;
+        }
+    }
+
+    totals
+        .iter()
+        .map(|(key, duration)| (key.clone(), to_hours(duration)))
+        .collect()
+}
+
+/// Hours of each key of a breakdown.
+fn breakdown_hours(breakdown: &BTreeMap<String, Duration>) -> BTreeMap<String, f32> {
+    breakdown
+        .iter()
+        .map(|(key, duration)| (key.clone(), to_hours(duration)))
+        .collect()
+}
+
+pub(crate) fn create_table() -> Table {
     let mut table = Table::new();
 
     let format = format::FormatBuilder::new()
@@ -30,39 +337,305 @@ fn print_results_stdout(times: &[CommitHours]) -> Result<()> {
         .build();
     table.set_format(format);
 
-    table.set_titles(row!["Author", "Email", "Commits", "Estimated Hours"]);
-    table.add_empty_row();
+    table
+}
 
-    for time in times.iter() {
-        let author = match &time.author_name {
-            Some(n) => n,
-            None => "",
-        };
-        let email = match &time.email {
-            Some(email) => email,
-            None => "(none)",
-        };
-        let commits = time.commit_count;
-        let estimated_hours = (time.duration.num_minutes() as f32) / 60.0;
+fn print_table_stdout(out: &mut dyn Write, report: &ReportTable, totals: bool) -> Result<()> {
+    let to_row = |cells: &[String]| Row::new(cells.iter().map(|c| Cell::new(c)).collect());
 
-        table.add_row(row![author, email, commits, estimated_hours]);
-    }
+    let mut table = create_table();
 
+    table.set_titles(to_row(&report.titles));
     table.add_empty_row();
 
-    let (total_estimated_hours, total_commits) = get_totals(times);
-    table.add_row(row!["Total", "", total_commits, total_estimated_hours]);
+    for row in report.rows.iter() {
+        table.add_row(to_row(row));
+    }
+
+    if totals {
+        table.add_empty_row();
+        table.add_row(to_row(&report.totals));
+    }
 
     log::debug!("Results: {:?}", table);
     log::debug!("");
-    // TODO: Tie this into the log printer.
-    table.printstd();
+    table.print(out)?;
+
+    Ok(())
+}
+
+fn print_table_delimited(
+    out: &mut dyn Write,
+    report: &ReportTable,
+    delimiter: u8,
+    totals: bool,
+) -> Result<()> {
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .from_writer(out);
+
+    writer.write_record(&report.titles)?;
+    for row in report.rows.iter() {
+        writer.write_record(row)?;
+    }
+    if totals {
+        writer.write_record(&report.totals)?;
+    }
+    writer.flush()?;
 
     Ok(())
 }
 
-fn print_results_json(times: &[CommitHours]) -> Result<()> {
-    let mut times_json = times.iter().map(CommitHoursJson::from).collect::<Vec<_>>();
+/// Escape a cell for a GitHub-flavored markdown table.
+fn escape_markdown(cell: &str) -> String {
+    cell.replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace('\n', " ")
+}
+
+fn print_table_markdown(out: &mut dyn Write, report: &ReportTable, totals: bool) -> Result<()> {
+    let to_line = |cells: &[String]| {
+        let cells = cells.iter().map(|c| escape_markdown(c)).collect::<Vec<_>>();
+        format!("| {} |", cells.join(" | "))
+    };
+
+    writeln!(out, "{}", to_line(&report.titles))?;
+    writeln!(out, "|{}", " --- |".repeat(report.titles.len()))?;
+    for row in report.rows.iter() {
+        writeln!(out, "{}", to_line(row))?;
+    }
+    if totals {
+        writeln!(out, "{}", to_line(&report.totals))?;
+    }
+
+    Ok(())
+}
+
+/// Escape text for HTML element content and attribute values.
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+const HTML_STYLE: &str = "\
+body { font-family: sans-serif; margin: 2em; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #ccc; padding: 0.3em 0.8em; text-align: left; }
+th { background: #f0f0f0; cursor: pointer; user-select: none; }
+tfoot td { font-weight: bold; }";
+
+/// Sorts the rows of the table body when a column title is clicked, numerically when possible.
+const HTML_SCRIPT: &str = "\
+document.querySelectorAll('th').forEach(function (th) {
+  th.addEventListener('click', function () {
+    // Pages hold several tables, so the column is counted within the table of the header.
+    var column = th.cellIndex;
+    var tbody = th.closest('table').querySelector('tbody');
+    var ascending = th.dataset.order !== 'ascending';
+    th.dataset.order = ascending ? 'ascending' : 'descending';
+    var rows = Array.prototype.slice.call(tbody.rows);
+    rows.sort(function (a, b) {
+      var x = a.cells[column].textContent, y = b.cells[column].textContent;
+      var order = (x !== '' && y !== '' && !isNaN(x) && !isNaN(y))
+        ? parseFloat(x) - parseFloat(y)
+        : x.localeCompare(y);
+      return ascending ? order : -order;
+    });
+    rows.forEach(function (row) { tbody.appendChild(row); });
+  });
+});";
+
+/// Write tables as one HTML page, each table after an optional heading.
+fn print_tables_html(
+    out: &mut dyn Write,
+    reports: &[(Option<&str>, &ReportTable)],
+    totals: bool,
+) -> Result<()> {
+    let to_cells = |tag: &str, cells: &[String]| {
+        cells
+            .iter()
+            .map(|c| format!("<{}>{}</{}>", tag, escape_html(c), tag))
+            .collect::<String>()
+    };
+
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str(&format!("<title>{}</title>\n", clap::crate_name!()));
+    html.push_str(&format!("<style>\n{}\n</style>\n", HTML_STYLE));
+    html.push_str("</head>\n<body>\n");
+    for (heading, report) in reports.iter() {
+        if let Some(heading) = heading {
+            html.push_str(&format!("<h2>{}</h2>\n", escape_html(heading)));
+        }
+        html.push_str("<table>\n");
+        html.push_str(&format!(
+            "<thead>\n<tr>{}</tr>\n</thead>\n",
+            to_cells("th", &report.titles)
+        ));
+        html.push_str("<tbody>\n");
+        for row in report.rows.iter() {
+            html.push_str(&format!("<tr>{}</tr>\n", to_cells("td", row)));
+        }
+        html.push_str("</tbody>\n");
+        if totals {
+            html.push_str(&format!(
+                "<tfoot>\n<tr>{}</tr>\n</tfoot>\n",
+                to_cells("td", &report.totals)
+            ));
+        }
+        html.push_str("</table>\n");
+    }
+    html.push_str(&format!("<script>\n{}\n</script>\n", HTML_SCRIPT));
+    html.push_str("</body>\n</html>");
+
+    writeln!(out, "{}", html)?;
+
+    Ok(())
+}
+
+fn print_table(
+    out: &mut dyn Write,
+    report: &ReportTable,
+    configuration: &Configuration,
+) -> Result<()> {
+    let totals = configuration.totals;
+    match &configuration.output_format {
+        OutputFormat::Stdout => print_table_stdout(out, report, totals),
+        OutputFormat::Csv => print_table_delimited(out, report, b',', totals),
+        OutputFormat::Tsv => print_table_delimited(out, report, b'\t', totals),
+        OutputFormat::Markdown => print_table_markdown(out, report, totals),
+        OutputFormat::Html => print_tables_html(out, &[(None, report)], totals),
+        OutputFormat::Json => unreachable!("JSON reports are not tabular"),
+    }
+}
+
+fn author_json(time: &CommitHours, by_repository: bool, by_component: bool) -> CommitHoursJson {
+    let mut time_json = CommitHoursJson::from(time);
+    if by_repository {
+        time_json.repositories = Some(
+            time.repositories
+                .iter()
+                .map(|(repository, time)| (repository.clone(), time.into()))
+                .collect(),
+        );
+    }
+    if by_component {
+        time_json.components = Some(breakdown_hours(&time.components));
+    }
+    time_json
+}
+
+fn session_json(session: &WorkSession, by_repository: bool) -> WorkSessionJson {
+    let mut session_json = WorkSessionJson::from(session);
+    if by_repository {
+        session_json.repositories = Some(
+            session
+                .repository_hours()
+                .iter()
+                .map(|(repository, time)| (repository.clone(), time.into()))
+                .collect(),
+        );
+    }
+    session_json
+}
+
+/// Versioned JSON report of the estimate, in the report shape of the configuration.
+fn report_json(estimate: &Estimate, configuration: &Configuration) -> ReportJson {
+    let times = &estimate.authors;
+    let periods = configuration
+        .group_by
+        .map(|group_by| group_by_period(times, group_by, configuration.time_zone));
+
+    let authors = times
+        .iter()
+        .enumerate()
+        .map(|(i, time)| {
+            let mut time_json = author_json(
+                time,
+                configuration.by_repository,
+                configuration.by_component,
+            );
+            if configuration.sessions {
+                time_json.sessions = Some(
+                    time.sessions
+                        .iter()
+                        .map(|session| session_json(session, configuration.by_repository))
+                        .collect(),
+                );
+            }
+            if let Some(periods) = &periods {
+                time_json.periods = Some(PeriodHoursJson::from(&periods[i]).periods);
+            }
+            time_json
+        })
+        .collect();
+
+    let (total_estimated_hours, total_commits) = get_totals(times);
+    let total_periods = periods
+        .as_ref()
+        .map(|periods| total_hours(periods.iter().map(|time| &time.periods)));
+    let total_components = if configuration.by_component {
+        Some(total_hours(times.iter().map(|time| &time.components)))
+    } else {
+        None
+    };
+
+    ReportJson {
+        schema_version: REPORT_SCHEMA_VERSION,
+        generated_at: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        repositories: estimate
+            .repositories
+            .iter()
+            .map(|repository| RepositoryJson {
+                name: repository.name.clone(),
+                path: repository.path.display().to_string(),
+            })
+            .collect(),
+        configuration: configuration.into(),
+        authors,
+        totals: TotalsJson {
+            hours: total_estimated_hours,
+            commit_count: total_commits,
+            periods: total_periods,
+            components: total_components,
+        },
+        excluded_bots: estimate
+            .bots
+            .iter()
+            .map(|bot| ExcludedAuthorJson {
+                email: bot.email.clone(),
+                author_name: bot.author_name.clone(),
+                commit_count: bot.commit_count,
+            })
+            .collect(),
+    }
+}
+
+fn print_report_json(
+    out: &mut dyn Write,
+    estimate: &Estimate,
+    configuration: &Configuration,
+) -> Result<()> {
+    let json = serde_json::to_string_pretty(&report_json(estimate, configuration))?;
+
+    writeln!(out, "{}", json)?;
+
+    Ok(())
+}
+
+fn print_results_json(
+    out: &mut dyn Write,
+    times: &[CommitHours],
+    by_repository: bool,
+    by_component: bool,
+) -> Result<()> {
+    let mut times_json = times
+        .iter()
+        .map(|time| author_json(time, by_repository, by_component))
+        .collect::<Vec<_>>();
 
     let (total_estimated_hours, total_commits) = get_totals(times);
     times_json.push(CommitHoursJson {
@@ -70,19 +643,170 @@ fn print_results_json(times: &[CommitHours]) -> Result<()> {
         author_name: Some(String::from("Total")),
         hours: total_estimated_hours,
         commit_count: total_commits,
+        repositories: None,
+        sessions: None,
+        periods: None,
+        components: None,
     });
 
     let json = serde_json::to_string_pretty(&times_json)?;
 
-    log::info!("{}", json);
+    writeln!(out, "{}", json)?;
+
+    Ok(())
+}
+
+fn print_sessions_json(
+    out: &mut dyn Write,
+    times: &[CommitHours],
+    by_repository: bool,
+) -> Result<()> {
+    let sessions_json = times
+        .iter()
+        .flat_map(|time| {
+            time.sessions
+                .iter()
+                .map(move |session| session_json(session, by_repository))
+        })
+        .collect::<Vec<_>>();
+
+    let json = serde_json::to_string_pretty(&sessions_json)?;
+
+    writeln!(out, "{}", json)?;
 
     Ok(())
 }
 
-/// Print times with the specified format.
-pub fn print_results(times: &[CommitHours], output_format: &OutputFormat) -> Result<()> {
-    match output_format {
-        OutputFormat::Stdout => print_results_stdout(times),
-        OutputFormat::Json => print_results_json(times),
+fn print_periods_json(out: &mut dyn Write, periods: &[PeriodHours]) -> Result<()> {
+    let periods_json = periods
+        .iter()
+        .map(PeriodHoursJson::from)
+        .collect::<Vec<_>>();
+
+    let json = serde_json::to_string_pretty(&periods_json)?;
+
+    writeln!(out, "{}", json)?;
+
+    Ok(())
+}
+
+/// Log the bots left out of an estimate, for formats that only hold the report itself.
+fn log_bots(bots: &[CommitHours]) {
+    for bot in bots.iter() {
+        let cells = author_cells(&bot.author_name, &bot.email);
+        log::info!(
+            "Excluded bot: {} <{}>, commits: {}",
+            cells[0],
+            cells[1],
+            bot.commit_count
+        );
+    }
+}
+
+/// Write the bots left out of an estimate after its report.
+fn print_bots(
+    out: &mut dyn Write,
+    bots: &[CommitHours],
+    configuration: &Configuration,
+) -> Result<()> {
+    if bots.is_empty() {
+        return Ok(());
+    }
+
+    match configuration.output_format {
+        OutputFormat::Stdout => {
+            writeln!(out)?;
+            writeln!(out, "Excluded bots:")?;
+        }
+        OutputFormat::Markdown => {
+            writeln!(out)?;
+            writeln!(out, "**Excluded bots**")?;
+            writeln!(out)?;
+        }
+        // Spreadsheets and legacy JSON hold a single table or array.
+        _ => {
+            log_bots(bots);
+            return Ok(());
+        }
+    }
+
+    print_table(out, &bots_table(bots), configuration)
+}
+
+/// Write an estimate with the format and report shape of the configuration.
+///
+/// Bots left out of the estimate are listed after the report, or logged when the format holds only
+/// the report.
+pub fn print_results(
+    out: &mut dyn Write,
+    estimate: &Estimate,
+    configuration: &Configuration,
+) -> Result<()> {
+    let times = &estimate.authors;
+
+    match configuration.output_format {
+        OutputFormat::Json if !configuration.json_legacy => {
+            // The report document lists the bots itself.
+            print_report_json(out, estimate, configuration)
+        }
+        OutputFormat::Json => {
+            print_legacy_json(out, times, configuration)?;
+            print_bots(out, &estimate.bots, configuration)
+        }
+        OutputFormat::Html => {
+            // The page is a self-contained report, so the bots get a table of their own.
+            let report = estimate_table(times, configuration);
+            let bots = bots_table(&estimate.bots);
+            let mut reports = vec![(None, &report)];
+            if !estimate.bots.is_empty() {
+                reports.push((Some("Excluded bots"), &bots));
+            }
+            print_tables_html(out, &reports, configuration.totals)
+        }
+        _ => {
+            print_table(out, &estimate_table(times, configuration), configuration)?;
+            print_bots(out, &estimate.bots, configuration)
+        }
+    }
+}
+
+/// Table of an estimate in the report shape of the configuration.
+fn estimate_table(times: &[CommitHours], configuration: &Configuration) -> ReportTable {
+    if let Some(group_by) = configuration.group_by {
+        let periods = group_by_period(times, group_by, configuration.time_zone);
+        return periods_table(&periods, configuration.by_repository);
+    }
+
+    if configuration.by_component {
+        return components_table(times);
+    }
+
+    if configuration.sessions {
+        sessions_table(times, configuration.by_repository)
+    } else {
+        authors_table(times, configuration.by_repository)
+    }
+}
+
+/// Legacy JSON array of an estimate in the report shape of the configuration.
+fn print_legacy_json(
+    out: &mut dyn Write,
+    times: &[CommitHours],
+    configuration: &Configuration,
+) -> Result<()> {
+    if let Some(group_by) = configuration.group_by {
+        let periods = group_by_period(times, group_by, configuration.time_zone);
+        return print_periods_json(out, &periods);
+    }
+
+    if configuration.sessions {
+        print_sessions_json(out, times, configuration.by_repository)
+    } else {
+        print_results_json(
+            out,
+            times,
+            configuration.by_repository,
+            configuration.by_component,
+        )
     }
 }
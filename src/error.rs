@@ -6,6 +6,7 @@ pub struct Error(ErrorKind);
 #[derive(Debug)]
 pub(crate) enum ErrorKind {
     FromStr(String),
+    Configuration(String),
 }
 
 impl Error {
@@ -18,6 +19,7 @@ impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match &self.0 {
             ErrorKind::FromStr(e) => fmt::Display::fmt(e, f),
+            ErrorKind::Configuration(e) => fmt::Display::fmt(e, f),
         }
     }
 }
@@ -25,7 +27,8 @@ impl fmt::Display for Error {
 impl std::error::Error for Error {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match &self.0 {
-            ErrorKind::FromStr(_) => None
+            ErrorKind::FromStr(_) => None,
+            ErrorKind::Configuration(_) => None,
         }
     }
 }
@@ -34,6 +37,7 @@ impl From<Error> for io::Error {
     fn from(e: Error) -> Self {
         match e.0 {
             ErrorKind::FromStr(e) => Self::new(io::ErrorKind::InvalidData, e),
+            ErrorKind::Configuration(e) => Self::new(io::ErrorKind::InvalidInput, e),
         }
     }
 }
@@ -1,11 +1,19 @@
+#![allow(deprecated)]
+
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
 use std::str::FromStr;
 
-use chrono::{Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime, Utc,
+    Weekday,
+};
+use git2::Oid;
 
 use crate::error;
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum CommitTimeBound {
     Always,
     Today,
@@ -45,7 +53,7 @@ impl CommitTimeBound {
                         - Duration::weeks(1);
                 Some(NaiveDateTime::new(date, zero()))
             }
-            Self::Date(date) => Some(NaiveDateTime::new(date.clone(), zero())),
+            Self::Date(date) => Some(NaiveDateTime::new(*date, zero())),
         }
     }
 }
@@ -71,15 +79,59 @@ impl FromStr for CommitTimeBound {
     }
 }
 
-impl ToString for CommitTimeBound {
-    fn to_string(&self) -> String {
+impl fmt::Display for CommitTimeBound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Always => write!(f, "always"),
+            Self::Today => write!(f, "today"),
+            Self::Yesterday => write!(f, "yesterday"),
+            Self::ThisWeek => write!(f, "thisweek"),
+            Self::LastWeek => write!(f, "lastweek"),
+            Self::Date(date) => write!(f, "{}", date),
+        }
+    }
+}
+
+/// Time zone used to place commit times into calendar periods.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ReportTimeZone {
+    Local,
+    Fixed(FixedOffset),
+}
+
+impl ReportTimeZone {
+    pub fn to_naive_date_time(&self, date_time: &DateTime<Utc>) -> NaiveDateTime {
         match self {
-            Self::Always => "always".into(),
-            Self::Today => "today".into(),
-            Self::Yesterday => "yesterday".into(),
-            Self::ThisWeek => "thisweek".into(),
-            Self::LastWeek => "lastweek".into(),
-            Self::Date(date) => date.to_string(),
+            Self::Local => date_time.with_timezone(&Local).naive_local(),
+            Self::Fixed(offset) => date_time.with_timezone(offset).naive_local(),
+        }
+    }
+}
+
+impl FromStr for ReportTimeZone {
+    type Err = error::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "local" => Ok(Self::Local),
+            "utc" => Ok(Self::Fixed(FixedOffset::east(0))),
+            x => match FixedOffset::from_str(x) {
+                Ok(offset) => Ok(Self::Fixed(offset)),
+                Err(_) => Err(error::Error::new(format!(
+                    "Could not parse time zone '{}' using local|utc|+HH:MM format",
+                    x
+                ))),
+            },
+        }
+    }
+}
+
+impl fmt::Display for ReportTimeZone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Local => write!(f, "local"),
+            Self::Fixed(offset) if offset.local_minus_utc() == 0 => write!(f, "utc"),
+            Self::Fixed(offset) => write!(f, "{}", offset),
         }
     }
 }
@@ -90,6 +142,70 @@ pub struct CommitHours {
     pub author_name: Option<String>,
     pub duration: Duration,
     pub commit_count: usize,
+    pub sessions: Vec<WorkSession>,
+    /// Estimated time and commits by repository name.
+    pub repositories: BTreeMap<String, RepositoryHours>,
+    /// Estimated time by component, empty unless components were computed.
+    pub components: BTreeMap<String, Duration>,
+}
+
+/// Estimated time and commits of one author in one repository.
+#[derive(Clone, Debug, Default)]
+pub struct RepositoryHours {
+    pub duration: Duration,
+    pub commit_count: usize,
+}
+
+/// Estimated time of one author bucketed into calendar periods.
+#[derive(Clone, Debug)]
+pub struct PeriodHours {
+    pub email: Option<String>,
+    pub author_name: Option<String>,
+    pub duration: Duration,
+    /// Estimated time by ISO period key, e.g. `2015-02-18`, `2015-W08`, `2015-02` or `2015`.
+    pub periods: BTreeMap<String, Duration>,
+    /// Estimated time by repository name and ISO period key.
+    pub repositories: BTreeMap<String, BTreeMap<String, Duration>>,
+}
+
+/// A coding session inferred from consecutive commits of one author.
+#[derive(Clone, Debug)]
+pub struct WorkSession {
+    pub email: Option<String>,
+    pub author_name: Option<String>,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub commits: Vec<Oid>,
+    /// Time added to compensate for the unknown work before the first commit.
+    pub first_commit_addition: Duration,
+    pub estimated_duration: Duration,
+    /// Estimated time of each commit, in the order of `commits`.
+    pub commit_durations: Vec<CommitDuration>,
+    /// Estimated time by repository name.
+    pub repositories: BTreeMap<String, Duration>,
+}
+
+impl WorkSession {
+    /// Estimated time and commits by repository name.
+    pub fn repository_hours(&self) -> BTreeMap<String, RepositoryHours> {
+        let mut repositories: BTreeMap<String, RepositoryHours> = BTreeMap::new();
+        for commit in self.commit_durations.iter() {
+            let hours = repositories.entry(commit.repository.clone()).or_default();
+            hours.duration += commit.duration;
+            hours.commit_count += 1;
+        }
+
+        repositories
+    }
+}
+
+/// Estimated time of one commit of a work session.
+#[derive(Clone, Debug)]
+pub struct CommitDuration {
+    pub repository: String,
+    pub time: DateTime<Utc>,
+    /// Time spent on the work leading up to the commit.
+    pub duration: Duration,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -98,6 +214,14 @@ pub struct CommitHoursJson {
     pub author_name: Option<String>,
     pub hours: f32,
     pub commit_count: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repositories: Option<BTreeMap<String, RepositoryHoursJson>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sessions: Option<Vec<WorkSessionJson>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub periods: Option<BTreeMap<String, f32>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub components: Option<BTreeMap<String, f32>>,
 }
 
 impl From<&CommitHours> for CommitHoursJson {
@@ -107,6 +231,158 @@ impl From<&CommitHours> for CommitHoursJson {
             author_name: time.author_name.clone(),
             hours: time.duration.num_minutes() as f32 / 60.0,
             commit_count: time.commit_count,
+            repositories: None,
+            sessions: None,
+            periods: None,
+            components: None,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RepositoryHoursJson {
+    pub hours: f32,
+    pub commit_count: usize,
+}
+
+impl From<&RepositoryHours> for RepositoryHoursJson {
+    fn from(time: &RepositoryHours) -> Self {
+        RepositoryHoursJson {
+            hours: time.duration.num_minutes() as f32 / 60.0,
+            commit_count: time.commit_count,
         }
     }
 }
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct WorkSessionJson {
+    pub email: Option<String>,
+    pub author_name: Option<String>,
+    pub start: String,
+    pub end: String,
+    pub commit_count: usize,
+    pub commits: Vec<String>,
+    pub first_commit_hours: f32,
+    pub hours: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repositories: Option<BTreeMap<String, RepositoryHoursJson>>,
+}
+
+impl From<&WorkSession> for WorkSessionJson {
+    fn from(session: &WorkSession) -> Self {
+        WorkSessionJson {
+            email: session.email.clone(),
+            author_name: session.author_name.clone(),
+            start: session.start.to_rfc3339(),
+            end: session.end.to_rfc3339(),
+            commit_count: session.commits.len(),
+            commits: session.commits.iter().map(|oid| oid.to_string()).collect(),
+            first_commit_hours: session.first_commit_addition.num_minutes() as f32 / 60.0,
+            hours: session.estimated_duration.num_minutes() as f32 / 60.0,
+            repositories: None,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PeriodHoursJson {
+    pub email: Option<String>,
+    pub author_name: Option<String>,
+    pub hours: f32,
+    pub periods: BTreeMap<String, f32>,
+}
+
+impl From<&PeriodHours> for PeriodHoursJson {
+    fn from(time: &PeriodHours) -> Self {
+        PeriodHoursJson {
+            email: time.email.clone(),
+            author_name: time.author_name.clone(),
+            hours: time.duration.num_minutes() as f32 / 60.0,
+            periods: time
+                .periods
+                .iter()
+                .map(|(period, duration)| (period.clone(), duration.num_minutes() as f32 / 60.0))
+                .collect(),
+        }
+    }
+}
+
+/// Version of the JSON report document, incremented on incompatible changes.
+///
+/// The document is described by `docs/report.schema.json`.
+pub const REPORT_SCHEMA_VERSION: u32 = 1;
+
+/// Versioned JSON report document.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ReportJson {
+    pub schema_version: u32,
+    pub generated_at: String,
+    pub repositories: Vec<RepositoryJson>,
+    pub configuration: ConfigurationJson,
+    pub authors: Vec<CommitHoursJson>,
+    pub totals: TotalsJson,
+    pub excluded_bots: Vec<ExcludedAuthorJson>,
+}
+
+/// An author left out of the report.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ExcludedAuthorJson {
+    pub email: Option<String>,
+    pub author_name: Option<String>,
+    pub commit_count: usize,
+}
+
+/// A repository the report was estimated from.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RepositoryJson {
+    pub name: String,
+    pub path: String,
+}
+
+/// Options the report was estimated with.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ConfigurationJson {
+    pub estimator: String,
+    pub diff_reference_lines: usize,
+    pub diff_exponent: f64,
+    pub diff_max_factor: f64,
+    pub diff_minutes_per_line: f64,
+    pub max_commit_diff_minutes: i64,
+    pub first_commit_addition_minutes: i64,
+    pub legacy_first_session: bool,
+    pub since: String,
+    pub until: String,
+    pub merges: String,
+    pub first_parent: bool,
+    pub recurse_submodules: bool,
+    pub email_aliases: BTreeMap<String, String>,
+    pub mailmap: bool,
+    pub authors: Vec<String>,
+    pub exclude_authors: Vec<String>,
+    pub bots: Vec<String>,
+    pub include_bots: bool,
+    pub branches: Vec<String>,
+    pub exclude_branches: Vec<String>,
+    pub tags: bool,
+    pub branch_type: String,
+    pub revisions: Vec<String>,
+    pub pathspecs: Vec<String>,
+    pub sessions: bool,
+    pub by_repository: bool,
+    pub by_component: bool,
+    pub component_depth: usize,
+    pub components: BTreeMap<String, Vec<String>>,
+    pub group_by: Option<String>,
+    pub time_zone: String,
+}
+
+/// Totals over every author of the report.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TotalsJson {
+    pub hours: f32,
+    pub commit_count: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub periods: Option<BTreeMap<String, f32>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub components: Option<BTreeMap<String, f32>>,
+}
@@ -0,0 +1,41 @@
+//! Estimate the amount of time spent working on a Git repository.
+//!
+//! The estimation pipeline is usable without the command line interface:
+//!
+//! ```no_run
+//! use chrono::Duration;
+//!
+//! let estimate = jikyuu::Statistics::new(".")
+//!     .max_commit_diff(Duration::minutes(120))
+//!     .first_commit_addition(Duration::minutes(30))
+//!     .run()
+//!     .unwrap();
+//!
+//! for author in estimate.authors {
+//!     println!("{:?}: {} sessions", author.email, author.sessions.len());
+//! }
+//! ```
+
+pub mod command;
+pub mod error;
+pub mod git;
+
+pub use command::statistics::{
+    commit_line_stats, estimate_author_times, estimate_author_times_with, filter_commits,
+    filter_commits_by_pathspec, find_repositories, get_commits, get_git_context, open_submodules,
+    resolve_commits, AnalyzedRepository, Estimate, LineStats, RepositoryCommit, Statistics,
+};
+pub use command::statistics_author::{partition_bots, AuthorFilter, AuthorPattern, DEFAULT_BOTS};
+pub use command::statistics_configuration::{
+    BranchKind, Configuration, ConfigurationBuilder, GroupBy, MergeCommits, OutputFormat,
+};
+pub use command::statistics_estimator::{
+    AuthorEstimate, CommitEstimate, DiffWeighted, DiffWeights, Estimator, EstimatorKind, GitHours,
+    SessionEstimate,
+};
+pub use git::{
+    CommitDuration, CommitHours, CommitTimeBound, PeriodHours, ReportJson, ReportTimeZone,
+    RepositoryHours, WorkSession, REPORT_SCHEMA_VERSION,
+};
+
+pub type ExitCode = i32;
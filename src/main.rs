@@ -1,17 +1,12 @@
-pub mod command;
-pub mod error;
-pub mod git;
-
 use anyhow::{bail, Result};
-use command::statistics::statistics;
-use command::statistics_configuration::OutputFormat;
+use jikyuu::command::config::config;
+use jikyuu::command::statistics::statistics;
+use jikyuu::{BranchKind, EstimatorKind, ExitCode, GroupBy, MergeCommits, OutputFormat};
 use log::{LevelFilter, Record};
 use std::env;
 use std::io::Write;
 use std::str::FromStr;
 
-type ExitCode = i32;
-
 type LogFormatter = Box<
     dyn Fn(&mut env_logger::fmt::Formatter, &Record) -> Result<(), std::io::Error> + Send + Sync,
 >;
@@ -82,82 +77,295 @@ fn create_application() -> clap::App<'static, 'static> {
                         .case_insensitive(true),
                 ),
         )
+        .subcommand(
+            clap::SubCommand::with_name("config")
+                .about("Inspect configuration files")
+                .subcommand(
+                    clap::SubCommand::with_name("show")
+                        .about("Print the effective configuration and the source of each value")
+                        .args(&configuration_arguments())
+                        .arg(
+                            clap::Arg::with_name("REPO_PATH")
+                                .help("Root path of the Git repository whose configuration to show.")
+                                .required(true)
+                                .default_value(".")
+                                .index(1),
+                        ),
+                ),
+        )
         .subcommand(
             clap::SubCommand::with_name("stats")
                 .alias("statistics")
                 .about("Print repository statistics")
-                .arg(clap::Arg::with_name("max-commit-diff")
-                     .long("max-commit-diff")
-                     .short("d")
-                     .help("Maximum difference in minutes between commits counted to one session")
-                     .takes_value(true)
-                     .value_name("MINUTES")
-                     .required(false)
-                     .default_value("120"))
-                .arg(clap::Arg::with_name("first-commit-add")
-                     .long("first-commit-add")
-                     .short("a")
-                     .help("How many minutes first commit of session should add to total")
-                     .takes_value(true)
-                     .value_name("MINUTES")
-                     .required(false)
-                     .default_value("30"))
-                .arg(clap::Arg::with_name("since")
-                     .long("since")
-                     .short("s")
-                     .help("Analyze data since certain date")
-                     .takes_value(true)
-                     .value_name("always|today|yesterday|thisweek|lastweek|YYYY-mm-dd")
-                     .required(false)
-                     .default_value("always"))
-                .arg(clap::Arg::with_name("until")
-                     .long("until")
-                     .short("u")
-                     .help("Analyze data until certain date")
-                     .takes_value(true)
-                     .value_name("always|today|yesterday|thisweek|lastweek|YYYY-mm-dd")
-                     .required(false)
-                     .default_value("always"))
-                .arg(clap::Arg::with_name("email")
-                     .long("email")
-                     .short("e")
-                     .help("Associate all commits that have a secondary email with a primary email")
+                .args(&configuration_arguments())
+                .arg(clap::Arg::with_name("output")
+                     .long("output")
+                     .short("o")
                      .takes_value(true)
-                     .multiple(true)
-                     .number_of_values(1)
-                     .value_name("OTHER_EMAIL=MAIN_EMAIL"))
-                .arg(clap::Arg::with_name("merge-requests")
-                     .long("merge-requests")
-                     .short("m")
-                     .help("Include merge requests into calculation"))
-                .arg(clap::Arg::with_name("branch")
-                     .long("branch")
-                     .short("b")
-                     .takes_value(true)
-                     .help("Analyze only data on the specified branch"))
-                .arg(clap::Arg::with_name("branch-type")
-                     .long("branch-type")
-                     .short("t")
-                     .takes_value(true)
-                     .value_name("local|remote")
-                     .requires("branch")
-                     .help("Type of branch that `branch` refers to. `local` means refs/heads/, `remote` means refs/remotes/."))
-                .arg(clap::Arg::with_name("format")
-                     .long("format")
-                     .short("f")
-                     .takes_value(true)
-                     .possible_values(&OutputFormat::variants())
-                     .case_insensitive(true)
-                     .required(false)
-                     .default_value("stdout"))
+                     .value_name("FILE")
+                     .help("Write the report to a file instead of stdout. Without `format`, the format follows the extension (.json, .csv, .tsv, .md, .html)."))
+                .arg(clap::Arg::with_name("force")
+                     .long("force")
+                     .requires("output")
+                     .help("Overwrite the output file if it exists"))
+                .arg(clap::Arg::with_name("scan")
+                     .long("scan")
+                     .help("Scan the repository paths for repositories below them"))
                 .arg(clap::Arg::with_name("REPO_PATH")
-                     .help("Root path of the Git repository to analyze.")
+                     .help("Root paths of the Git repositories to analyze. Commits of the same author are merged across repositories. The repository configuration is read from the first path.")
                      .required(true)
+                     .multiple(true)
                      .default_value(".")
                      .index(1))
+                .arg(clap::Arg::with_name("PATHSPEC")
+                     .help("Analyze only commits whose changes against their first parent touch paths matching a git pathspec, given after `--`, e.g. `-- src/backend`.")
+                     .multiple(true)
+                     .last(true))
         )
 }
 
+/// Arguments of `stats` that set configuration values, also accepted by `config show`.
+fn configuration_arguments() -> Vec<clap::Arg<'static, 'static>> {
+    vec![
+        clap::Arg::with_name("estimator")
+             .long("estimator")
+             .takes_value(true)
+             .value_name("NAME")
+             .possible_values(&EstimatorKind::variants())
+             .case_insensitive(true)
+             .help("Model the time spent working is estimated with: the `git-hours` heuristic, or `diff-weighted` to weight it by the lines each commit changed"),
+        clap::Arg::with_name("diff-reference-lines")
+             .long("diff-reference-lines")
+             .takes_value(true)
+             .value_name("LINES")
+             .help("Lines changed by a commit that adds exactly the first commit addition with the diff-weighted estimator")
+             .default_value("100"),
+        clap::Arg::with_name("diff-exponent")
+             .long("diff-exponent")
+             .takes_value(true)
+             .value_name("EXPONENT")
+             .help("How the first commit addition grows with the lines changed with the diff-weighted estimator, 1 being linear")
+             .default_value("0.5"),
+        clap::Arg::with_name("diff-max-factor")
+             .long("diff-max-factor")
+             .takes_value(true)
+             .value_name("FACTOR")
+             .help("Largest multiple of the first commit addition a commit adds with the diff-weighted estimator")
+             .default_value("4"),
+        clap::Arg::with_name("diff-minutes-per-line")
+             .long("diff-minutes-per-line")
+             .takes_value(true)
+             .value_name("MINUTES")
+             .help("Minutes per line changed that the time since the previous commit is capped at with the diff-weighted estimator")
+             .default_value("5"),
+        clap::Arg::with_name("max-commit-diff")
+             .long("max-commit-diff")
+             .short("d")
+             .help("Maximum difference in minutes between commits counted to one session")
+             .takes_value(true)
+             .value_name("MINUTES")
+             .required(false)
+             .default_value("120"),
+        clap::Arg::with_name("first-commit-add")
+             .long("first-commit-add")
+             .short("a")
+             .help("How many minutes first commit of session should add to total")
+             .takes_value(true)
+             .value_name("MINUTES")
+             .required(false)
+             .default_value("30"),
+        clap::Arg::with_name("legacy-first-session")
+             .long("legacy-first-session")
+             .help("Add nothing for the first commit of the first session of each author, like earlier versions"),
+        clap::Arg::with_name("no-legacy-first-session")
+             .long("no-legacy-first-session")
+             .conflicts_with("legacy-first-session")
+             .help("Add the first commit addition to every session, overriding `legacy-first-session` of configuration files"),
+        clap::Arg::with_name("since")
+             .long("since")
+             .short("s")
+             .help("Analyze data since certain date")
+             .takes_value(true)
+             .value_name("always|today|yesterday|thisweek|lastweek|YYYY-mm-dd")
+             .required(false)
+             .default_value("always"),
+        clap::Arg::with_name("until")
+             .long("until")
+             .short("u")
+             .help("Analyze data until certain date")
+             .takes_value(true)
+             .value_name("always|today|yesterday|thisweek|lastweek|YYYY-mm-dd")
+             .required(false)
+             .default_value("always"),
+        clap::Arg::with_name("email")
+             .long("email")
+             .short("e")
+             .help("Associate all commits that have a secondary email with a primary email")
+             .takes_value(true)
+             .multiple(true)
+             .number_of_values(1)
+             .value_name("OTHER_EMAIL=MAIN_EMAIL"),
+        clap::Arg::with_name("no-mailmap")
+             .long("no-mailmap")
+             .help("Do not resolve author identities through the repository .mailmap"),
+        clap::Arg::with_name("mailmap")
+             .long("mailmap")
+             .conflicts_with("no-mailmap")
+             .help("Resolve author identities through the repository .mailmap, overriding `mailmap` of configuration files"),
+        clap::Arg::with_name("author")
+             .long("author")
+             .takes_value(true)
+             .multiple(true)
+             .number_of_values(1)
+             .value_name("AUTHOR")
+             .help("Report only authors whose email or name matches, after aliases are resolved. Accepts emails, names and regexes and can be given several times."),
+        clap::Arg::with_name("exclude-author")
+             .long("exclude-author")
+             .takes_value(true)
+             .multiple(true)
+             .number_of_values(1)
+             .value_name("AUTHOR")
+             .help("Leave out authors whose email or name matches an email, name or regex"),
+        clap::Arg::with_name("include-bots")
+             .long("include-bots")
+             .help("Report bots and automation accounts like dependabot[bot] instead of leaving them out"),
+        clap::Arg::with_name("exclude-bots")
+             .long("exclude-bots")
+             .conflicts_with("include-bots")
+             .help("Leave out bots and automation accounts, overriding `include-bots` of configuration files"),
+        clap::Arg::with_name("merges")
+             .long("merges")
+             .takes_value(true)
+             .possible_values(&MergeCommits::variants())
+             .case_insensitive(true)
+             .help("How merge commits are counted: `exclude` commits with several parents, `include` them, include them but only follow the `first-parent` of merges, or exclude commits whose `message` starts with \"Merge \""),
+        clap::Arg::with_name("first-parent")
+             .long("first-parent")
+             .help("Walk only the first parent of merge commits, ignoring the commits of merged side branches"),
+        clap::Arg::with_name("no-first-parent")
+             .long("no-first-parent")
+             .conflicts_with("first-parent")
+             .help("Walk every parent of merge commits, overriding `first-parent` of configuration files"),
+        clap::Arg::with_name("merge-requests")
+             .long("merge-requests")
+             .short("m")
+             .conflicts_with("merges")
+             .help("Include merge commits into calculation, same as `--merges include`"),
+        clap::Arg::with_name("branch")
+             .long("branch")
+             .short("b")
+             .takes_value(true)
+             .multiple(true)
+             .number_of_values(1)
+             .help("Analyze only data on the specified branch. Accepts globs like `release/*` and `feature/**` and can be given several times."),
+        clap::Arg::with_name("exclude-branch")
+             .long("exclude-branch")
+             .takes_value(true)
+             .multiple(true)
+             .number_of_values(1)
+             .value_name("BRANCH")
+             .help("Skip the branches matching a name or glob"),
+        clap::Arg::with_name("tags")
+             .long("tags")
+             .help("Also analyze the history of every tag"),
+        clap::Arg::with_name("no-tags")
+             .long("no-tags")
+             .conflicts_with("tags")
+             .help("Analyze only the history of branches, overriding `tags` of configuration files"),
+        clap::Arg::with_name("branch-type")
+             .long("branch-type")
+             .short("t")
+             .takes_value(true)
+             .possible_values(&BranchKind::variants())
+             .case_insensitive(true)
+             .help("Type of branch that `branch` refers to. `local` means refs/heads/, `remote` means refs/remotes/, `all` means both."),
+        clap::Arg::with_name("revision")
+             .long("revision")
+             .short("r")
+             .takes_value(true)
+             .multiple(true)
+             .number_of_values(1)
+             .value_name("REVISION")
+             .conflicts_with_all(&["branch", "exclude-branch", "branch-type", "tags"])
+             .help("Analyze only the commits of a git revision or range, e.g. `v1.2.0..v1.3.0`, `main...feature`, `HEAD~50..` or `^old-tag`"),
+        clap::Arg::with_name("format")
+             .long("format")
+             .short("f")
+             .takes_value(true)
+             .possible_values(&OutputFormat::variants())
+             .case_insensitive(true)
+             .required(false)
+             .default_value("stdout"),
+        clap::Arg::with_name("json-legacy")
+             .long("json-legacy")
+             .help("Print the legacy JSON array, with the total as an author named `Total`"),
+        clap::Arg::with_name("no-json-legacy")
+             .long("no-json-legacy")
+             .conflicts_with("json-legacy")
+             .help("Print the versioned JSON report, overriding `json-legacy` of configuration files"),
+        clap::Arg::with_name("no-totals")
+             .long("no-totals")
+             .help("Omit the totals row from tabular output"),
+        clap::Arg::with_name("totals")
+             .long("totals")
+             .conflicts_with("no-totals")
+             .help("Print the totals row of tabular output, overriding `totals` of configuration files"),
+        clap::Arg::with_name("sessions")
+             .long("sessions")
+             .help("Print every inferred work session instead of totals by author"),
+        clap::Arg::with_name("no-sessions")
+             .long("no-sessions")
+             .conflicts_with("sessions")
+             .help("Print totals by author instead of sessions, overriding `sessions` of configuration files"),
+        clap::Arg::with_name("group-by")
+             .long("group-by")
+             .short("g")
+             .takes_value(true)
+             .possible_values(&GroupBy::variants())
+             .case_insensitive(true)
+             .conflicts_with("sessions")
+             .help("Break down estimated hours by calendar period"),
+        clap::Arg::with_name("time-zone")
+             .long("time-zone")
+             .short("z")
+             .takes_value(true)
+             .value_name("local|utc|+HH:MM")
+             .allow_hyphen_values(true)
+             .required(false)
+             .default_value("local")
+             .help("Time zone used to place sessions into calendar periods"),
+        clap::Arg::with_name("by-repository")
+             .long("by-repository")
+             .help("Break down estimated hours by repository"),
+        clap::Arg::with_name("no-by-repository")
+             .long("no-by-repository")
+             .conflicts_with("by-repository")
+             .help("Do not break down estimated hours by repository, overriding `by-repository` of configuration files"),
+        clap::Arg::with_name("by-component")
+             .long("by-component")
+             .conflicts_with_all(&["sessions", "group-by", "by-repository"])
+             .help("Break down estimated hours by component, splitting the time of each commit by the lines it changed"),
+        clap::Arg::with_name("no-by-component")
+             .long("no-by-component")
+             .conflicts_with("by-component")
+             .help("Do not break down estimated hours by component, overriding `by-component` of configuration files"),
+        clap::Arg::with_name("component-depth")
+             .long("component-depth")
+             .takes_value(true)
+             .value_name("DEPTH")
+             .required(false)
+             .default_value("1")
+             .help("Directory depth of components, 1 being the top-level directories, unless components are configured"),
+        clap::Arg::with_name("recurse-submodules")
+             .long("recurse-submodules")
+             .help("Include the commits of every submodule, recursively"),
+        clap::Arg::with_name("no-recurse-submodules")
+             .long("no-recurse-submodules")
+             .conflicts_with("recurse-submodules")
+             .help("Leave out the commits of submodules, overriding `recurse-submodules` of configuration files"),
+    ]
+}
+
 /// Initializes the application logger.
 fn initialize_logger(args: &clap::ArgMatches) {
     let args_log_level = args.value_of("verbosity").unwrap_or("error");
@@ -190,7 +398,7 @@ fn initialize_logger(args: &clap::ArgMatches) {
     env_logger::Builder::from_env(env)
         .filter_level(log_level)
         .format(log_format)
-        .target(env_logger::Target::Stdout)
+        .target(env_logger::Target::Stderr)
         .init();
 }
 
@@ -244,6 +452,8 @@ fn run(args: &clap::ArgMatches) -> Result<ExitCode> {
 
     if args.subcommand_matches("completions").is_some() {
         exit_code = completions(args)?;
+    } else if args.subcommand_matches("config").is_some() {
+        exit_code = config(args)?;
     } else if args.subcommand_matches("stats").is_some() {
         exit_code = statistics(args)?;
     } else {
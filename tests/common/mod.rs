@@ -0,0 +1,118 @@
+#![allow(dead_code)]
+
+use chrono::DateTime;
+use git2::{Oid, Repository, Signature, Time};
+
+pub fn create_commit_initial(
+    repository: &Repository,
+    time: String,
+) -> Result<Oid, Box<dyn std::error::Error>> {
+    let username = "Nate-Wilkins";
+    let email = "nate-wilkins@code-null.com";
+
+    let signature = Signature::new(
+        username,
+        email,
+        &Time::new(DateTime::parse_from_rfc2822(&time).unwrap().timestamp(), 0),
+    )?;
+
+    let oid = repository.index().unwrap().write_tree().unwrap();
+    let tree = repository.find_tree(oid).unwrap();
+    let oid_commit = repository
+        .commit(Some("HEAD"), &signature, &signature, "init 1", &tree, &[])
+        .unwrap();
+
+    Ok(oid_commit)
+}
+
+/// Create a commit in the provided repository at a specific time.
+pub fn create_commit(
+    repository: &Repository,
+    time: String,
+    message: String,
+) -> Result<Oid, Box<dyn std::error::Error>> {
+    create_commit_by(
+        repository,
+        time,
+        message,
+        "Nate-Wilkins",
+        "nate-wilkins@code-null.com",
+    )
+}
+
+/// Create a commit in the provided repository at a specific time by a specific author.
+pub fn create_commit_by(
+    repository: &Repository,
+    time: String,
+    message: String,
+    username: &str,
+    email: &str,
+) -> Result<Oid, Box<dyn std::error::Error>> {
+    let mut index = repository.index()?;
+    index.add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)?;
+
+    let tree_id = index.write_tree()?;
+    let tree = repository.find_tree(tree_id)?;
+    let parent_commit = repository.head().unwrap().peel_to_commit().unwrap();
+
+    let signature = Signature::new(
+        username,
+        email,
+        &Time::new(DateTime::parse_from_rfc2822(&time).unwrap().timestamp(), 0),
+    )?;
+    let oid_commit = repository.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        &message,
+        &tree,
+        &[&parent_commit],
+    )?;
+
+    Ok(oid_commit)
+}
+
+/// Create a commit with specific parents without moving `HEAD`, e.g. on a side branch.
+pub fn create_commit_detached(
+    repository: &Repository,
+    time: String,
+    message: String,
+    parents: &[Oid],
+) -> Result<Oid, Box<dyn std::error::Error>> {
+    let tree_id = repository.index()?.write_tree()?;
+    let tree = repository.find_tree(tree_id)?;
+    let parent_commits = parents
+        .iter()
+        .map(|oid| repository.find_commit(*oid))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let signature = Signature::new(
+        "Nate-Wilkins",
+        "nate-wilkins@code-null.com",
+        &Time::new(DateTime::parse_from_rfc2822(&time).unwrap().timestamp(), 0),
+    )?;
+    let oid_commit = repository.commit(
+        None,
+        &signature,
+        &signature,
+        &message,
+        &tree,
+        &parent_commits.iter().collect::<Vec<_>>(),
+    )?;
+
+    Ok(oid_commit)
+}
+
+/// Create a merge commit of `HEAD` and another commit, moving `HEAD` to it.
+pub fn create_commit_merge(
+    repository: &Repository,
+    time: String,
+    message: String,
+    other: Oid,
+) -> Result<Oid, Box<dyn std::error::Error>> {
+    let head = repository.head()?.peel_to_commit()?.id();
+    let oid_commit = create_commit_detached(repository, time, message, &[head, other])?;
+    repository.head()?.set_target(oid_commit, "merge")?;
+
+    Ok(oid_commit)
+}
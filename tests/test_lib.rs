@@ -0,0 +1,210 @@
+extern crate pretty_assertions;
+extern crate tempfile;
+
+mod common;
+
+use chrono::Duration;
+use common::{create_commit, create_commit_detached, create_commit_initial, create_commit_merge};
+use git2::{Oid, Repository};
+use pretty_assertions::assert_eq;
+use tempfile::tempdir;
+
+#[test]
+fn test_library_statistics_repository() -> Result<(), Box<dyn std::error::Error>> {
+    // Given we have a repository.
+    let path_repository = tempdir().unwrap().path().join("");
+    let repository = Repository::init(&path_repository)?;
+    create_commit_initial(&repository, String::from("Wed, 18 Feb 2015 10:10:09 GMT"))?;
+    create_commit(
+        &repository,
+        String::from("Wed, 18 Feb 2015 11:10:09 GMT"),
+        String::from("Commit A"),
+    )?;
+    create_commit(
+        &repository,
+        String::from("Wed, 18 Feb 2015 18:01:00 GMT"),
+        String::from("Commit B"),
+    )?;
+
+    // When the library runs statistics.
+    let estimate = jikyuu::Statistics::new(&path_repository)
+        .max_commit_diff(Duration::minutes(120))
+        .first_commit_addition(Duration::minutes(30))
+        .run()?;
+
+    // Then statistics were estimated for the single author.
+    assert_eq!(estimate.authors.len(), 1);
+    let author = &estimate.authors[0];
+    assert_eq!(author.email.as_deref(), Some("nate-wilkins@code-null.com"));
+    assert_eq!(author.author_name.as_deref(), Some("Nate-Wilkins"));
+    assert_eq!(author.commit_count, 3);
+    assert_eq!(author.sessions.len(), 2);
+    assert_eq!(author.duration, Duration::minutes(120));
+
+    // When the library runs statistics without time for the first session, like earlier versions.
+    let estimate = jikyuu::Statistics::new(&path_repository)
+        .max_commit_diff(Duration::minutes(120))
+        .first_commit_addition(Duration::minutes(30))
+        .legacy_first_session(true)
+        .run()?;
+
+    // Then only the later session got time for its first commit.
+    assert_eq!(estimate.authors[0].duration, Duration::minutes(90));
+
+    Ok(())
+}
+
+#[test]
+fn test_library_configuration_validation() -> Result<(), Box<dyn std::error::Error>> {
+    // Given a configuration that ends before it starts.
+    let builder = jikyuu::Configuration::builder()
+        .since("2015-02-18".parse()?)
+        .until("2015-02-17".parse()?);
+
+    // When the configuration is built.
+    let result = builder.build();
+
+    // Then a validation error is reported.
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "Since '2015-02-18' must not be after until '2015-02-17'."
+    );
+
+    // Given a configuration with revisions and tags, which revisions do not walk.
+    let builder = jikyuu::Configuration::builder()
+        .revisions(["v1.0.0..v1.1.0"])
+        .tags(true);
+
+    // Then a validation error is reported.
+    assert_eq!(
+        builder.build().unwrap_err().to_string(),
+        "Excluded branches, tags and branch types cannot be combined with revisions."
+    );
+
+    // And the default configuration is valid.
+    jikyuu::Configuration::default().validate()?;
+
+    Ok(())
+}
+
+#[test]
+fn test_library_statistics_merges() -> Result<(), Box<dyn std::error::Error>> {
+    // Given we have a repository with a merged side branch.
+    let path_repository = tempdir().unwrap().path().join("");
+    let repository = Repository::init(&path_repository)?;
+    let initial =
+        create_commit_initial(&repository, String::from("Wed, 18 Feb 2015 10:00:00 GMT"))?;
+    let feature = create_commit_detached(
+        &repository,
+        String::from("Wed, 18 Feb 2015 10:30:00 GMT"),
+        String::from("Feature"),
+        &[initial],
+    )?;
+    // And a regular commit whose message looks like a merge.
+    let sort = create_commit(
+        &repository,
+        String::from("Wed, 18 Feb 2015 11:00:00 GMT"),
+        String::from("Merge sort optimisation"),
+    )?;
+    let merge = create_commit_merge(
+        &repository,
+        String::from("Wed, 18 Feb 2015 11:30:00 GMT"),
+        String::from("Integrate feature"),
+        feature,
+    )?;
+
+    let commits = |merges: jikyuu::MergeCommits| -> Result<Vec<Oid>, Box<dyn std::error::Error>> {
+        // When the library runs statistics with the merge mode.
+        let estimate = jikyuu::Statistics::new(&path_repository)
+            .merges(merges)
+            .run()?;
+        Ok(estimate.authors[0].sessions[0].commits.clone())
+    };
+
+    // Then merge commits are detected by their parents.
+    assert_eq!(
+        commits(jikyuu::MergeCommits::Exclude)?,
+        vec![initial, feature, sort]
+    );
+    assert_eq!(
+        commits(jikyuu::MergeCommits::Include)?,
+        vec![initial, feature, sort, merge]
+    );
+    // And the side branch is skipped when following first parents.
+    assert_eq!(
+        commits(jikyuu::MergeCommits::FirstParent)?,
+        vec![initial, sort, merge]
+    );
+    // And the message heuristic drops the regular commit instead of the merge.
+    assert_eq!(
+        commits(jikyuu::MergeCommits::Message)?,
+        vec![initial, feature, merge]
+    );
+
+    Ok(())
+}
+
+/// Estimates one session per commit of one hour each.
+struct HourPerCommit;
+
+impl jikyuu::Estimator for HourPerCommit {
+    fn estimate<'a, 'repo>(
+        &self,
+        commits: &[&'a jikyuu::RepositoryCommit<'repo>],
+        _configuration: &jikyuu::Configuration,
+    ) -> jikyuu::AuthorEstimate<'a, 'repo> {
+        jikyuu::AuthorEstimate {
+            sessions: commits
+                .iter()
+                .map(|commit| jikyuu::SessionEstimate {
+                    commits: vec![jikyuu::CommitEstimate {
+                        commit,
+                        duration: Duration::hours(1),
+                    }],
+                })
+                .collect(),
+        }
+    }
+}
+
+#[test]
+fn test_library_statistics_estimator() -> Result<(), Box<dyn std::error::Error>> {
+    // Given we have a repository with one session.
+    let path_repository = tempdir().unwrap().path().join("");
+    let repository = Repository::init(&path_repository)?;
+    create_commit_initial(&repository, String::from("Wed, 18 Feb 2015 10:00:00 GMT"))?;
+    create_commit(
+        &repository,
+        String::from("Wed, 18 Feb 2015 10:30:00 GMT"),
+        String::from("Commit A"),
+    )?;
+    let configuration = jikyuu::Configuration::default();
+    let resolve = || -> Result<Vec<jikyuu::RepositoryCommit>, Box<dyn std::error::Error>> {
+        let commits = jikyuu::get_commits(&repository, &configuration)?;
+        Ok(jikyuu::resolve_commits("repository", commits, None)?)
+    };
+
+    // When the library estimates the commits with the default estimator.
+    let authors = jikyuu::estimate_author_times(&configuration, resolve()?);
+
+    // Then the commits were estimated as one session.
+    assert_eq!(authors[0].sessions.len(), 1);
+    assert_eq!(authors[0].duration, Duration::minutes(60));
+
+    // When the library estimates the commits with its own estimator.
+    let authors = jikyuu::estimate_author_times_with(&HourPerCommit, &configuration, resolve()?);
+
+    // Then the sessions and time of that estimator were reported.
+    assert_eq!(authors[0].sessions.len(), 2);
+    assert_eq!(
+        authors[0].sessions[1].first_commit_addition,
+        Duration::hours(1)
+    );
+    assert_eq!(authors[0].duration, Duration::hours(2));
+    assert_eq!(
+        authors[0].repositories["repository"].duration,
+        Duration::hours(2)
+    );
+
+    Ok(())
+}
@@ -3,70 +3,20 @@ extern crate predicates;
 extern crate pretty_assertions;
 extern crate tempfile;
 
+mod common;
+
 use assert_cmd::prelude::*;
-use chrono::DateTime;
-use git2::{Oid, Repository, Signature, Time};
+use common::{
+    create_commit, create_commit_by, create_commit_detached, create_commit_initial,
+    create_commit_merge,
+};
+use git2::{Oid, Repository};
 use predicates::prelude::*;
 use std::process::Command;
 use tempfile::tempdir;
 
 const BIN: &str = "jikyuu";
 
-fn create_commit_initial(
-    repository: &Repository,
-    time: String,
-) -> Result<Oid, Box<dyn std::error::Error>> {
-    let username = "Nate-Wilkins";
-    let email = "nate-wilkins@code-null.com";
-
-    let signature = Signature::new(
-        username,
-        email,
-        &Time::new(DateTime::parse_from_rfc2822(&time).unwrap().timestamp(), 0),
-    )?;
-
-    let oid = repository.index().unwrap().write_tree().unwrap();
-    let tree = repository.find_tree(oid).unwrap();
-    let oid_commit = repository
-        .commit(Some("HEAD"), &signature, &signature, "init 1", &tree, &[])
-        .unwrap();
-
-    Ok(oid_commit)
-}
-
-/// Create a commit in the provided repository at a specific time.
-fn create_commit(
-    repository: &Repository,
-    time: String,
-    message: String,
-) -> Result<Oid, Box<dyn std::error::Error>> {
-    let username = "Nate-Wilkins";
-    let email = "nate-wilkins@code-null.com";
-
-    let mut index = repository.index()?;
-    index.add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)?;
-
-    let tree_id = index.write_tree()?;
-    let tree = repository.find_tree(tree_id)?;
-    let parent_commit = repository.head().unwrap().peel_to_commit().unwrap();
-
-    let signature = Signature::new(
-        username,
-        email,
-        &Time::new(DateTime::parse_from_rfc2822(&time).unwrap().timestamp(), 0),
-    )?;
-    let oid_commit = repository.commit(
-        Some("HEAD"),
-        &signature,
-        &signature,
-        &message,
-        &tree,
-        &[&parent_commit],
-    )?;
-
-    Ok(oid_commit)
-}
-
 #[test]
 fn test_command_completions_type_zsh() -> Result<(), Box<dyn std::error::Error>> {
     // Given the CLI.
@@ -118,9 +68,9 @@ fn test_command_statistics_repository() -> Result<(), Box<dyn std::error::Error>
 +--------------+----------------------------+---------+-----------------+
 | Author       | Email                      | Commits | Estimated Hours |
 |              |                            |         |                 |
-| Nate-Wilkins | nate-wilkins@code-null.com | 3       | 1.8333334       |
+| Nate-Wilkins | nate-wilkins@code-null.com | 3       | 2.3333333       |
 |              |                            |         |                 |
-| Total        |                            | 3       | 1.8333334       |
+| Total        |                            | 3       | 2.3333333       |
 +--------------+----------------------------+---------+-----------------+
 "
             .trim(),
@@ -181,8 +131,6 @@ fn test_command_statistics_submodule() -> Result<(), Box<dyn std::error::Error>>
 
     // When the user runs the command statistics.
     let result = cmd
-        .arg("--verbosity")
-        .arg("debug")
         .arg("statistics")
         .arg(path_repository_main_submodule_a)
         .assert();
@@ -197,9 +145,9 @@ fn test_command_statistics_submodule() -> Result<(), Box<dyn std::error::Error>>
 +--------------+----------------------------+---------+-----------------+
 | Author       | Email                      | Commits | Estimated Hours |
 |              |                            |         |                 |
-| Nate-Wilkins | nate-wilkins@code-null.com | 3       | 2.3333333       |
+| Nate-Wilkins | nate-wilkins@code-null.com | 3       | 2.8333333       |
 |              |                            |         |                 |
-| Total        |                            | 3       | 2.3333333       |
+| Total        |                            | 3       | 2.8333333       |
 +--------------+----------------------------+---------+-----------------+
 "
             .trim(),
@@ -207,3 +155,1645 @@ fn test_command_statistics_submodule() -> Result<(), Box<dyn std::error::Error>>
 
     Ok(())
 }
+
+#[test]
+fn test_command_statistics_sessions() -> Result<(), Box<dyn std::error::Error>> {
+    // Given the CLI.
+    let mut cmd = Command::cargo_bin(BIN)?;
+
+    // And we have a repository with two work sessions.
+    let path_repository = tempdir().unwrap().path().join("");
+    let repository = Repository::init(&path_repository)?;
+    create_commit_initial(&repository, String::from("Wed, 18 Feb 2015 10:10:09 GMT"))?;
+    create_commit(
+        &repository,
+        String::from("Wed, 18 Feb 2015 11:10:09 GMT"),
+        String::from("Commit A"),
+    )?;
+    create_commit(
+        &repository,
+        String::from("Wed, 18 Feb 2015 18:01:00 GMT"),
+        String::from("Commit B"),
+    )?;
+
+    // When the user runs the command statistics with sessions.
+    let result = cmd
+        .arg("statistics")
+        .arg("--sessions")
+        .arg(path_repository)
+        .assert();
+
+    result
+        // Then no errors occurred.
+        .success()
+        .stderr(predicate::str::is_empty())
+        // Then every session was outputted.
+        .stdout(predicate::str::contains(
+            "
+| Author       | Email                      | Start                     | End                       | Commits | First Commit Hours | Estimated Hours |
+|              |                            |                           |                           |         |                    |                 |
+| Nate-Wilkins | nate-wilkins@code-null.com | 2015-02-18T10:10:09+00:00 | 2015-02-18T11:10:09+00:00 | 2       | 0.5                | 1.5             |
+| Nate-Wilkins | nate-wilkins@code-null.com | 2015-02-18T18:01:00+00:00 | 2015-02-18T18:01:00+00:00 | 1       | 0.5                | 0.5             |
+|              |                            |                           |                           |         |                    |                 |
+| Total        |                            |                           |                           | 3       | 1                  | 2               |
+"
+            .trim(),
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn test_command_statistics_group_by_day() -> Result<(), Box<dyn std::error::Error>> {
+    // Given the CLI.
+    let mut cmd = Command::cargo_bin(BIN)?;
+
+    // And we have a repository with a session spanning midnight.
+    let path_repository = tempdir().unwrap().path().join("");
+    let repository = Repository::init(&path_repository)?;
+    create_commit_initial(&repository, String::from("Wed, 18 Feb 2015 10:10:00 GMT"))?;
+    create_commit(
+        &repository,
+        String::from("Wed, 18 Feb 2015 23:30:00 GMT"),
+        String::from("Commit A"),
+    )?;
+    create_commit(
+        &repository,
+        String::from("Thu, 19 Feb 2015 00:30:00 GMT"),
+        String::from("Commit B"),
+    )?;
+
+    // When the user runs the command statistics grouped by day.
+    let result = cmd
+        .arg("statistics")
+        .arg("--group-by")
+        .arg("day")
+        .arg("--time-zone")
+        .arg("utc")
+        .arg(&path_repository)
+        .assert();
+
+    result
+        // Then no errors occurred.
+        .success()
+        .stderr(predicate::str::is_empty())
+        // Then the session time was split across both days.
+        .stdout(predicate::str::contains(
+            "
+| Author       | Email                      | 2015-02-18 | 2015-02-19 | Total |
+|              |                            |            |            |       |
+| Nate-Wilkins | nate-wilkins@code-null.com | 1.5        | 0.5        | 2     |
+|              |                            |            |            |       |
+| Total        |                            | 1.5        | 0.5        | 2     |
+"
+            .trim(),
+        ));
+
+    // When the user runs the command statistics grouped by day in a negative time zone.
+    let result = Command::cargo_bin(BIN)?
+        .arg("statistics")
+        .arg("-g")
+        .arg("day")
+        .arg("-z")
+        .arg("-05:00")
+        .arg("--format")
+        .arg("csv")
+        .arg(&path_repository)
+        .assert();
+
+    // Then the whole session fell on the local day.
+    result
+        .success()
+        .stdout(predicate::str::contains("Author,Email,2015-02-18,Total\n"));
+
+    Ok(())
+}
+
+#[test]
+fn test_command_statistics_group_by_day_ending_at_midnight(
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Given we have a repository with a session ending exactly at midnight.
+    let path_repository = tempdir().unwrap().path().join("");
+    let repository = Repository::init(&path_repository)?;
+    create_commit_initial(&repository, String::from("Wed, 18 Feb 2015 23:00:00 GMT"))?;
+    create_commit(
+        &repository,
+        String::from("Thu, 19 Feb 2015 00:00:00 GMT"),
+        String::from("Commit A"),
+    )?;
+
+    // When the user runs the command statistics grouped by day.
+    let output = Command::cargo_bin(BIN)?
+        .arg("statistics")
+        .arg("--group-by")
+        .arg("day")
+        .arg("--time-zone")
+        .arg("utc")
+        .arg("--format")
+        .arg("json")
+        .arg(&path_repository)
+        .output()?;
+
+    // Then the session was counted in the day it ended at without an empty next day.
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(
+        report["totals"]["periods"],
+        serde_json::json!({ "2015-02-18": 1.5 })
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_command_statistics_mailmap() -> Result<(), Box<dyn std::error::Error>> {
+    // Given the CLI.
+    let mut cmd = Command::cargo_bin(BIN)?;
+
+    // And we have a repository where the author committed with a secondary email.
+    let path_repository = tempdir().unwrap().path().join("");
+    let repository = Repository::init(&path_repository)?;
+    create_commit_initial(&repository, String::from("Wed, 18 Feb 2015 10:10:09 GMT"))?;
+    create_commit_by(
+        &repository,
+        String::from("Wed, 18 Feb 2015 11:10:09 GMT"),
+        String::from("Commit A"),
+        "Nate",
+        "nate@example.com",
+    )?;
+
+    // And the repository maps the secondary email to the primary email.
+    std::fs::write(
+        path_repository.join(".mailmap"),
+        "Nate-Wilkins <nate-wilkins@code-null.com> <nate@example.com>\n",
+    )?;
+
+    // When the user runs the command statistics.
+    let result = cmd.arg("statistics").arg(path_repository).assert();
+
+    result
+        // Then no errors occurred.
+        .success()
+        .stderr(predicate::str::is_empty())
+        // Then both commits were associated with the primary email.
+        .stdout(predicate::str::contains(
+            "
+| Nate-Wilkins | nate-wilkins@code-null.com | 2       | 1.5             |
+"
+            .trim(),
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn test_command_statistics_configuration_file() -> Result<(), Box<dyn std::error::Error>> {
+    // Given the CLI without a user configuration.
+    let mut cmd = Command::cargo_bin(BIN)?;
+    let path_config_home = tempdir()?;
+
+    // And we have a repository with two sessions.
+    let path_repository = tempdir().unwrap().path().join("");
+    let repository = Repository::init(&path_repository)?;
+    create_commit_initial(&repository, String::from("Wed, 18 Feb 2015 10:10:09 GMT"))?;
+    create_commit(
+        &repository,
+        String::from("Wed, 18 Feb 2015 18:10:09 GMT"),
+        String::from("Commit A"),
+    )?;
+
+    // And the repository configures the first commit addition and an email alias.
+    std::fs::write(
+        path_repository.join(".jikyuu.toml"),
+        "first-commit-add = 60\n\n[email]\n\"nate-wilkins@code-null.com\" = \"nate@example.com\"\n",
+    )?;
+
+    // When the user runs the command statistics overriding the first commit addition.
+    let result = cmd
+        .env("XDG_CONFIG_HOME", path_config_home.path())
+        .arg("statistics")
+        .arg("--first-commit-add")
+        .arg("90")
+        .arg(&path_repository)
+        .assert();
+
+    result
+        // Then no errors occurred.
+        .success()
+        .stderr(predicate::str::is_empty())
+        // Then the configuration file and the command line were both applied.
+        .stdout(predicate::str::contains(
+            "
+| Nate-Wilkins | nate@example.com | 2       | 3               |
+"
+            .trim(),
+        ));
+
+    // When the user shows the effective configuration.
+    let result = Command::cargo_bin(BIN)?
+        .env("XDG_CONFIG_HOME", path_config_home.path())
+        .arg("config")
+        .arg("show")
+        .arg(&path_repository)
+        .assert();
+
+    result
+        // Then no errors occurred.
+        .success()
+        .stderr(predicate::str::is_empty())
+        // Then the source of each value was outputted.
+        .stdout(predicate::str::is_match(format!(
+            r"\| first-commit-add +\| 60 +\| repository \({}\) +\|",
+            regex::escape(&path_repository.join(".jikyuu.toml").display().to_string())
+        ))?)
+        .stdout(predicate::str::is_match(
+            r"\| max-commit-diff +\| 120 +\| default +\|",
+        )?);
+
+    // When the user shows the configuration with options of the command statistics.
+    let result = Command::cargo_bin(BIN)?
+        .env("XDG_CONFIG_HOME", path_config_home.path())
+        .arg("config")
+        .arg("show")
+        .arg("--first-commit-add")
+        .arg("90")
+        .arg(&path_repository)
+        .assert();
+
+    // Then the options took precedence over the configuration file.
+    result.success().stdout(predicate::str::is_match(
+        r"\| first-commit-add +\| 90 +\| command line +\|",
+    )?);
+
+    // When the repository enables sessions and the user turns them off on the command line.
+    std::fs::write(path_repository.join(".jikyuu.toml"), "sessions = true\n")?;
+    let result = Command::cargo_bin(BIN)?
+        .env("XDG_CONFIG_HOME", path_config_home.path())
+        .arg("statistics")
+        .arg("--no-sessions")
+        .arg("--format")
+        .arg("csv")
+        .arg(&path_repository)
+        .assert();
+
+    // Then the command line took precedence over the configuration file.
+    result.success().stdout(predicate::str::starts_with(
+        "Author,Email,Commits,Estimated Hours\n",
+    ));
+
+    // When the user estimates another repository with its own configuration file after this one.
+    let path_repository_other = tempdir().unwrap().path().join("");
+    let repository_other = Repository::init(&path_repository_other)?;
+    create_commit_initial(
+        &repository_other,
+        String::from("Wed, 18 Feb 2015 10:00:00 GMT"),
+    )?;
+    std::fs::write(
+        path_repository_other.join(".jikyuu.toml"),
+        "sessions = false\n",
+    )?;
+    let result = Command::cargo_bin(BIN)?
+        .env("XDG_CONFIG_HOME", path_config_home.path())
+        .arg("statistics")
+        .arg("--format")
+        .arg("csv")
+        .arg(&path_repository)
+        .arg(&path_repository_other)
+        .assert();
+
+    // Then only the configuration of the first repository applied and the other file was named.
+    result
+        .success()
+        .stdout(predicate::str::starts_with("Author,Email,Start,End,"))
+        .stderr(predicate::str::contains(format!(
+            "Ignoring '{}'",
+            path_repository_other.join(".jikyuu.toml").display()
+        )));
+
+    Ok(())
+}
+
+#[test]
+fn test_command_statistics_multiple_repositories() -> Result<(), Box<dyn std::error::Error>> {
+    // Given the CLI.
+    let mut cmd = Command::cargo_bin(BIN)?;
+
+    // And we have a directory with two repositories the author alternated between.
+    let path_workspace = tempdir()?;
+    let path_repository_a = path_workspace.path().join("repository_a");
+    let path_repository_b = path_workspace.path().join("repository_b");
+    let repository_a = Repository::init(&path_repository_a)?;
+    let repository_b = Repository::init(&path_repository_b)?;
+    create_commit_initial(&repository_a, String::from("Wed, 18 Feb 2015 10:00:00 GMT"))?;
+    create_commit_initial(&repository_b, String::from("Wed, 18 Feb 2015 10:30:00 GMT"))?;
+    create_commit(
+        &repository_a,
+        String::from("Wed, 18 Feb 2015 11:00:00 GMT"),
+        String::from("Commit A"),
+    )?;
+
+    // When the user runs the command statistics on the directory by repository.
+    let result = cmd
+        .arg("statistics")
+        .arg("--scan")
+        .arg("--by-repository")
+        .arg(path_workspace.path())
+        .assert();
+
+    result
+        // Then no errors occurred.
+        .success()
+        .stderr(predicate::str::is_empty())
+        // Then the commits were merged into one session split across the repositories.
+        .stdout(predicate::str::contains(
+            "
+| Author       | Email                      | Repository   | Commits | Estimated Hours |
+|              |                            |              |         |                 |
+| Nate-Wilkins | nate-wilkins@code-null.com | repository_a | 2       | 1               |
+| Nate-Wilkins | nate-wilkins@code-null.com | repository_b | 1       | 0.5             |
+|              |                            |              |         |                 |
+| Total        |                            |              | 3       | 1.5             |
+"
+            .trim(),
+        ));
+
+    // When the user runs the command statistics on the directory by repository and session.
+    let result = Command::cargo_bin(BIN)?
+        .arg("statistics")
+        .arg("--scan")
+        .arg("--by-repository")
+        .arg("--sessions")
+        .arg("--format")
+        .arg("csv")
+        .arg(path_workspace.path())
+        .assert();
+
+    // Then each session was split across the repositories.
+    result.success().stdout(predicate::str::similar(
+        "\
+Author,Email,Start,End,Repository,Commits,First Commit Hours,Estimated Hours
+Nate-Wilkins,nate-wilkins@code-null.com,2015-02-18T10:00:00+00:00,2015-02-18T11:00:00+00:00,repository_a,2,0.5,1
+Nate-Wilkins,nate-wilkins@code-null.com,2015-02-18T10:00:00+00:00,2015-02-18T11:00:00+00:00,repository_b,1,0,0.5
+Total,,,,,3,0.5,1.5
+",
+    ));
+
+    // When the user runs the command statistics on the directory by repository and day.
+    let result = Command::cargo_bin(BIN)?
+        .arg("statistics")
+        .arg("--scan")
+        .arg("--by-repository")
+        .arg("--group-by")
+        .arg("day")
+        .arg("--time-zone")
+        .arg("utc")
+        .arg("--format")
+        .arg("csv")
+        .arg(path_workspace.path())
+        .assert();
+
+    // Then each period was split across the repositories.
+    result.success().stdout(predicate::str::similar(
+        "\
+Author,Email,Repository,2015-02-18,Total
+Nate-Wilkins,nate-wilkins@code-null.com,repository_a,1,1
+Nate-Wilkins,nate-wilkins@code-null.com,repository_b,0.5,0.5
+Total,,,1.5,1.5
+",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_command_statistics_scan_distinct_repositories() -> Result<(), Box<dyn std::error::Error>> {
+    // Given we have a directory with two repositories of the same name and a symbolic link loop.
+    let path_workspace = tempdir()?;
+    for (directory, time) in [
+        ("a", "Wed, 18 Feb 2015 10:00:00 GMT"),
+        ("b", "Wed, 18 Feb 2015 18:00:00 GMT"),
+    ] {
+        let repository = Repository::init(path_workspace.path().join(directory).join("app"))?;
+        create_commit_initial(&repository, String::from(time))?;
+    }
+    std::os::unix::fs::symlink("..", path_workspace.path().join("a").join("loop"))?;
+
+    let report = |args: &[&str]| -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+        let output = Command::cargo_bin(BIN)?
+            .arg("statistics")
+            .arg("--by-repository")
+            .arg("--format")
+            .arg("json")
+            .args(args)
+            .output()?;
+        assert!(output.status.success());
+        Ok(serde_json::from_slice(&output.stdout)?)
+    };
+    let path_a = path_workspace.path().join("a").join("app");
+    let path_a = path_a.to_str().unwrap();
+
+    // When the user scans the directory.
+    let report_scan = report(&["--scan", path_workspace.path().to_str().unwrap()])?;
+
+    // Then each repository was counted once under a distinct name.
+    let names = report_scan["repositories"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|repository| repository["name"].as_str().unwrap().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["a/app", "b/app"]);
+    assert_eq!(report_scan["totals"]["commit_count"], 2);
+    assert_eq!(
+        report_scan["authors"][0]["repositories"]["a/app"]["commit_count"],
+        1
+    );
+
+    // When the user passes the same repository twice.
+    let report_twice = report(&[path_a, path_a])?;
+
+    // Then the repository was counted once.
+    assert_eq!(report_twice["repositories"].as_array().unwrap().len(), 1);
+    assert_eq!(report_twice["repositories"][0]["name"], "app");
+    assert_eq!(report_twice["totals"]["commit_count"], 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_command_statistics_recurse_submodules() -> Result<(), Box<dyn std::error::Error>> {
+    // Given the CLI.
+    let mut cmd = Command::cargo_bin(BIN)?;
+
+    // And we have a repository submodule.
+    let path_repository_a = tempdir().unwrap().path().join("");
+    let repository_a = Repository::init(&path_repository_a)?;
+    create_commit_initial(&repository_a, String::from("Wed, 18 Feb 2015 10:00:00 GMT"))?;
+    create_commit(
+        &repository_a,
+        String::from("Wed, 18 Feb 2015 10:30:00 GMT"),
+        String::from("Commit A A"),
+    )?;
+
+    // And we have a repository that has the submodule.
+    let path_repository_main = tempdir().unwrap().path().join("");
+    let repository_main = Repository::init(&path_repository_main)?;
+    let repository_a_url = format!(
+        "file://{}.git",
+        path_repository_a.into_os_string().to_str().unwrap()
+    );
+    let mut submodule = repository_main.submodule(
+        &repository_a_url,
+        std::path::Path::new("packages/submodule_a"),
+        true,
+    )?;
+    submodule.clone(None)?;
+    create_commit_initial(
+        &repository_main,
+        String::from("Wed, 18 Feb 2015 11:00:00 GMT"),
+    )?;
+
+    // When the user runs the command statistics recursing into submodules by repository.
+    let result = cmd
+        .arg("statistics")
+        .arg("--recurse-submodules")
+        .arg("--by-repository")
+        .arg(&path_repository_main)
+        .assert();
+
+    result
+        // Then no errors occurred.
+        .success()
+        .stderr(predicate::str::is_empty())
+        // Then the time spent in the submodule was broken down.
+        .stdout(predicate::str::is_match(
+            r"\| Nate-Wilkins \| nate-wilkins@code-null.com \| [^ ]+/packages/submodule_a \| 2 +\| 1 +\|",
+        )?)
+        .stdout(predicate::str::is_match(
+            r"\| Total +\| +\| +\| 3 +\| 1.5 +\|",
+        )?);
+
+    Ok(())
+}
+
+#[test]
+fn test_command_statistics_format_csv() -> Result<(), Box<dyn std::error::Error>> {
+    // Given the CLI.
+    let mut cmd = Command::cargo_bin(BIN)?;
+
+    // And we have a repository with an author whose name contains a comma and quotes.
+    let path_repository = tempdir().unwrap().path().join("");
+    let repository = Repository::init(&path_repository)?;
+    create_commit_initial(&repository, String::from("Wed, 18 Feb 2015 10:10:09 GMT"))?;
+    create_commit_by(
+        &repository,
+        String::from("Wed, 18 Feb 2015 11:10:09 GMT"),
+        String::from("Commit A"),
+        "Wilkins, \"Nate\" Jr",
+        "nate@example.com",
+    )?;
+    create_commit_by(
+        &repository,
+        String::from("Wed, 18 Feb 2015 11:40:09 GMT"),
+        String::from("Commit B"),
+        "Wilkins, \"Nate\" Jr",
+        "nate@example.com",
+    )?;
+
+    // When the user runs the command statistics as CSV.
+    let result = cmd
+        .arg("statistics")
+        .arg("--format")
+        .arg("csv")
+        .arg(path_repository)
+        .assert();
+
+    result
+        // Then no errors occurred.
+        .success()
+        .stderr(predicate::str::is_empty())
+        // Then the statistics were outputted as quoted CSV with a totals row.
+        .stdout(predicate::str::similar(
+            "\
+Author,Email,Commits,Estimated Hours
+\"Wilkins, \"\"Nate\"\" Jr\",nate@example.com,2,1
+Nate-Wilkins,nate-wilkins@code-null.com,1,0.5
+Total,,3,1.5
+",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn test_command_statistics_format_markdown_html() -> Result<(), Box<dyn std::error::Error>> {
+    // Given we have a repository.
+    let path_repository = tempdir().unwrap().path().join("");
+    let repository = Repository::init(&path_repository)?;
+    create_commit_initial(&repository, String::from("Wed, 18 Feb 2015 10:10:09 GMT"))?;
+    create_commit_by(
+        &repository,
+        String::from("Wed, 18 Feb 2015 11:10:09 GMT"),
+        String::from("Commit A"),
+        "Nate | Wilkins & Co",
+        "nate@example.com",
+    )?;
+    create_commit_by(
+        &repository,
+        String::from("Wed, 18 Feb 2015 11:40:09 GMT"),
+        String::from("Commit B"),
+        "Nate | Wilkins & Co",
+        "nate@example.com",
+    )?;
+
+    // When the user runs the command statistics as markdown.
+    let result = Command::cargo_bin(BIN)?
+        .arg("statistics")
+        .arg("--format")
+        .arg("markdown")
+        .arg(&path_repository)
+        .assert();
+
+    result
+        // Then no errors occurred.
+        .success()
+        .stderr(predicate::str::is_empty())
+        // Then the statistics were outputted as a markdown table.
+        .stdout(predicate::str::similar(
+            "\
+| Author | Email | Commits | Estimated Hours |
+| --- | --- | --- | --- |
+| Nate \\| Wilkins & Co | nate@example.com | 2 | 1 |
+| Nate-Wilkins | nate-wilkins@code-null.com | 1 | 0.5 |
+| Total |  | 3 | 1.5 |
+",
+        ));
+
+    // When the user runs the command statistics as HTML.
+    let result = Command::cargo_bin(BIN)?
+        .arg("statistics")
+        .arg("--format")
+        .arg("html")
+        .arg(&path_repository)
+        .assert();
+
+    result
+        // Then no errors occurred.
+        .success()
+        .stderr(predicate::str::is_empty())
+        // Then the statistics were outputted as an escaped HTML table with a totals row.
+        .stdout(predicate::str::starts_with("<!DOCTYPE html>"))
+        .stdout(predicate::str::contains(
+            "<tr><td>Nate | Wilkins &amp; Co</td><td>nate@example.com</td><td>2</td><td>1</td></tr>",
+        ))
+        .stdout(predicate::str::contains(
+            "<tfoot>\n<tr><td>Total</td><td></td><td>3</td><td>1.5</td></tr>\n</tfoot>",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn test_command_statistics_format_json() -> Result<(), Box<dyn std::error::Error>> {
+    // Given we have a repository.
+    let path_repository = tempdir().unwrap().path().join("");
+    let repository = Repository::init(&path_repository)?;
+    create_commit_initial(&repository, String::from("Wed, 18 Feb 2015 10:10:09 GMT"))?;
+    create_commit(
+        &repository,
+        String::from("Wed, 18 Feb 2015 11:10:09 GMT"),
+        String::from("Commit A"),
+    )?;
+
+    // When the user runs the command statistics as JSON grouped by day.
+    let output = Command::cargo_bin(BIN)?
+        .arg("statistics")
+        .arg("--format")
+        .arg("json")
+        .arg("--group-by")
+        .arg("day")
+        .arg("--time-zone")
+        .arg("utc")
+        .arg(&path_repository)
+        .output()?;
+
+    // Then a versioned report was outputted.
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(report["schema_version"], 1);
+    assert!(report["generated_at"].is_string());
+    assert_eq!(report["repositories"].as_array().unwrap().len(), 1);
+    assert_eq!(report["configuration"]["group_by"], "day");
+
+    // And the total is not one of the authors.
+    let authors = report["authors"].as_array().unwrap();
+    assert_eq!(authors.len(), 1);
+    assert_eq!(authors[0]["author_name"], "Nate-Wilkins");
+    assert_eq!(authors[0]["periods"]["2015-02-18"], 1.5);
+    assert_eq!(report["totals"]["commit_count"], 2);
+    assert_eq!(report["totals"]["hours"], 1.5);
+    assert_eq!(report["totals"]["periods"]["2015-02-18"], 1.5);
+
+    // When the user asks for the legacy JSON array.
+    let output = Command::cargo_bin(BIN)?
+        .arg("statistics")
+        .arg("--format")
+        .arg("json")
+        .arg("--json-legacy")
+        .arg(&path_repository)
+        .output()?;
+
+    // Then the total was appended as an author.
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let authors = report.as_array().unwrap();
+    assert_eq!(authors.len(), 2);
+    assert_eq!(authors[1]["author_name"], "Total");
+    assert_eq!(authors[1]["email"], serde_json::Value::Null);
+
+    Ok(())
+}
+
+#[test]
+fn test_command_statistics_output_independent_of_logger() -> Result<(), Box<dyn std::error::Error>>
+{
+    // Given we have a repository.
+    let path_repository = tempdir().unwrap().path().join("");
+    let repository = Repository::init(&path_repository)?;
+    create_commit_initial(&repository, String::from("Wed, 18 Feb 2015 10:10:09 GMT"))?;
+
+    // When the user runs the command statistics as JSON with verbose contextual logs.
+    let output = Command::cargo_bin(BIN)?
+        .arg("--verbosity")
+        .arg("debug")
+        .arg("--log-format")
+        .arg("context")
+        .arg("statistics")
+        .arg("--format")
+        .arg("json")
+        .arg(&path_repository)
+        .output()?;
+
+    // Then the logs were written to stderr.
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stderr)?.contains("DEBUG"));
+
+    // And stdout holds nothing but the report.
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(report["schema_version"], 1);
+
+    // When the user silences every log but errors.
+    let output = Command::cargo_bin(BIN)?
+        .arg("--verbosity")
+        .arg("error")
+        .arg("statistics")
+        .arg("--format")
+        .arg("json")
+        .arg(&path_repository)
+        .output()?;
+
+    // Then the report was still written.
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(report["totals"]["commit_count"], 1);
+
+    // When the user writes the report to a file.
+    let path_output = tempdir()?.into_path().join("report.txt");
+    let result = Command::cargo_bin(BIN)?
+        .arg("statistics")
+        .arg("--output")
+        .arg(&path_output)
+        .arg(&path_repository)
+        .assert();
+
+    // Then nothing was written to stdout.
+    result.success().stdout(predicate::str::is_empty());
+
+    // And the report was written to the file.
+    let report = std::fs::read_to_string(&path_output)?;
+    assert!(report
+        .contains("| Nate-Wilkins | nate-wilkins@code-null.com | 1       | 0.5             |"));
+
+    Ok(())
+}
+
+#[test]
+fn test_command_statistics_output_file() -> Result<(), Box<dyn std::error::Error>> {
+    // Given we have a repository.
+    let path_repository = tempdir().unwrap().path().join("");
+    let repository = Repository::init(&path_repository)?;
+    create_commit_initial(&repository, String::from("Wed, 18 Feb 2015 10:10:09 GMT"))?;
+
+    // When the user writes the report to a JSON file without a format.
+    let path_directory = tempdir()?;
+    let path_output = path_directory.path().join("report.json");
+    Command::cargo_bin(BIN)?
+        .arg("statistics")
+        .arg("--output")
+        .arg(&path_output)
+        .arg(&path_repository)
+        .assert()
+        .success();
+
+    // Then the format was inferred from the extension.
+    let report: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path_output)?)?;
+    assert_eq!(report["schema_version"], 1);
+
+    // When the user writes the report to the same file again.
+    let result = Command::cargo_bin(BIN)?
+        .arg("statistics")
+        .arg("--format")
+        .arg("csv")
+        .arg("--output")
+        .arg(&path_output)
+        .arg(&path_repository)
+        .assert();
+
+    // Then the existing file was not overwritten.
+    result.failure().stderr(predicate::str::contains(
+        "already exists. Pass --force to overwrite it.",
+    ));
+    serde_json::from_str::<serde_json::Value>(&std::fs::read_to_string(&path_output)?)?;
+
+    // When the user forces the overwrite with an explicit format.
+    Command::cargo_bin(BIN)?
+        .arg("statistics")
+        .arg("--format")
+        .arg("csv")
+        .arg("--output")
+        .arg(&path_output)
+        .arg("--force")
+        .arg(&path_repository)
+        .assert()
+        .success();
+
+    // Then the explicit format was written.
+    assert!(std::fs::read_to_string(&path_output)?
+        .starts_with("Author,Email,Commits,Estimated Hours\n"));
+
+    // And no temporary file was left behind.
+    assert_eq!(std::fs::read_dir(path_directory.path())?.count(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_command_statistics_first_parent() -> Result<(), Box<dyn std::error::Error>> {
+    // Given we have a repository with a merged side branch.
+    let path_repository = tempdir().unwrap().path().join("");
+    let repository = Repository::init(&path_repository)?;
+    let initial =
+        create_commit_initial(&repository, String::from("Wed, 18 Feb 2015 10:00:00 GMT"))?;
+    let feature = create_commit_detached(
+        &repository,
+        String::from("Wed, 18 Feb 2015 10:30:00 GMT"),
+        String::from("Feature"),
+        &[initial],
+    )?;
+    create_commit(
+        &repository,
+        String::from("Wed, 18 Feb 2015 11:00:00 GMT"),
+        String::from("Mainline"),
+    )?;
+    create_commit_merge(
+        &repository,
+        String::from("Wed, 18 Feb 2015 11:30:00 GMT"),
+        String::from("Integrate feature"),
+        feature,
+    )?;
+
+    // When the user runs the command statistics on the mainline history.
+    let output = Command::cargo_bin(BIN)?
+        .arg("statistics")
+        .arg("--format")
+        .arg("json")
+        .arg("--first-parent")
+        .arg(&path_repository)
+        .output()?;
+
+    // Then the side branch and the excluded merge commit were not counted.
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(report["configuration"]["first_parent"], true);
+    assert_eq!(report["totals"]["commit_count"], 2);
+    assert_eq!(report["totals"]["hours"], 1.5);
+
+    Ok(())
+}
+
+#[test]
+fn test_command_statistics_revision_range() -> Result<(), Box<dyn std::error::Error>> {
+    // Given we have a repository with two releases.
+    let path_repository = tempdir().unwrap().path().join("");
+    let repository = Repository::init(&path_repository)?;
+    create_commit_initial(&repository, String::from("Wed, 18 Feb 2015 10:00:00 GMT"))?;
+    let release_a = create_commit(
+        &repository,
+        String::from("Wed, 18 Feb 2015 11:00:00 GMT"),
+        String::from("Release A"),
+    )?;
+    repository.tag_lightweight("v1.0.0", &repository.find_object(release_a, None)?, false)?;
+    create_commit(
+        &repository,
+        String::from("Wed, 18 Feb 2015 18:00:00 GMT"),
+        String::from("Commit B"),
+    )?;
+    let release_b = create_commit(
+        &repository,
+        String::from("Wed, 18 Feb 2015 18:30:00 GMT"),
+        String::from("Release B"),
+    )?;
+    repository.tag_lightweight("v1.1.0", &repository.find_object(release_b, None)?, false)?;
+    create_commit(
+        &repository,
+        String::from("Wed, 18 Feb 2015 19:00:00 GMT"),
+        String::from("Commit C"),
+    )?;
+
+    let totals = |revisions: &[&str]| -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin(BIN)?;
+        cmd.arg("statistics").arg("--format").arg("json");
+        for revision in revisions {
+            cmd.arg("--revision").arg(revision);
+        }
+        let output = cmd.arg(&path_repository).output()?;
+        assert!(output.status.success());
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+        Ok(report["totals"]["commit_count"].clone())
+    };
+
+    // When the user runs the command statistics on revisions.
+    // Then only the commits of the release range were counted.
+    assert_eq!(totals(&["v1.0.0..v1.1.0"])?, 2);
+    assert_eq!(totals(&["v1.0.0...v1.1.0"])?, 2);
+    // And an open range ends at HEAD.
+    assert_eq!(totals(&["v1.1.0.."])?, 1);
+    assert_eq!(totals(&["HEAD~2.."])?, 2);
+    // And a negated revision hides its history.
+    assert_eq!(totals(&["HEAD", "^v1.0.0"])?, 3);
+
+    // When the repository configures branches.
+    std::fs::write(
+        path_repository.join(".jikyuu.toml"),
+        "branch = \"main\"\ntags = true\n",
+    )?;
+
+    // Then revisions on the command line take precedence over the configured branches.
+    assert_eq!(totals(&["v1.0.0..v1.1.0"])?, 2);
+
+    // When the user runs the command statistics on revisions and tags.
+    let result = Command::cargo_bin(BIN)?
+        .arg("statistics")
+        .arg("--revision")
+        .arg("v1.0.0..v1.1.0")
+        .arg("--tags")
+        .arg(&path_repository)
+        .assert();
+
+    // Then an error was reported instead of ignoring the tags.
+    result
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+
+    // When the user runs the command statistics on an unknown revision.
+    let result = Command::cargo_bin(BIN)?
+        .arg("statistics")
+        .arg("--revision")
+        .arg("v9.9.9..")
+        .arg(&path_repository)
+        .assert();
+
+    // Then an error was reported.
+    result
+        .failure()
+        .stderr(predicate::str::contains("Invalid revision 'v9.9.9..'."));
+
+    Ok(())
+}
+
+#[test]
+fn test_command_statistics_branch_globs() -> Result<(), Box<dyn std::error::Error>> {
+    // Given we have a repository with a family of branches and a tag.
+    let path_repository = tempdir().unwrap().path().join("");
+    let repository = Repository::init(&path_repository)?;
+    let initial =
+        create_commit_initial(&repository, String::from("Wed, 18 Feb 2015 10:00:00 GMT"))?;
+    let branch = |name: &str, time: &str, parent: Oid| -> Result<Oid, Box<dyn std::error::Error>> {
+        let oid = create_commit_detached(&repository, String::from(time), name.into(), &[parent])?;
+        repository.reference(name, oid, false, "test")?;
+        Ok(oid)
+    };
+    let release = branch(
+        "refs/heads/release/1.0",
+        "Wed, 18 Feb 2015 10:10:00 GMT",
+        initial,
+    )?;
+    branch(
+        "refs/heads/release/2.0/hotfix",
+        "Wed, 18 Feb 2015 10:20:00 GMT",
+        release,
+    )?;
+    branch(
+        "refs/heads/feature/a/b",
+        "Wed, 18 Feb 2015 10:30:00 GMT",
+        initial,
+    )?;
+    branch("refs/tags/v0.1.0", "Wed, 18 Feb 2015 10:40:00 GMT", initial)?;
+
+    let totals = |args: &[&str]| -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+        let output = Command::cargo_bin(BIN)?
+            .arg("statistics")
+            .arg("--format")
+            .arg("json")
+            .args(args)
+            .arg(&path_repository)
+            .output()?;
+        assert!(output.status.success());
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+        Ok(report["totals"]["commit_count"].clone())
+    };
+
+    // When the user runs the command statistics on branches.
+    // Then every local branch is analyzed by default.
+    assert_eq!(totals(&[])?, 4);
+    // And `*` matches within one path segment.
+    assert_eq!(totals(&["--branch", "release/*"])?, 2);
+    // And `**` matches across path segments.
+    assert_eq!(totals(&["--branch", "release/**"])?, 3);
+    // And excluded branches are skipped.
+    assert_eq!(
+        totals(&["--branch", "release/**", "--exclude-branch", "*/*/hotfix"])?,
+        2
+    );
+    // And several branches are combined.
+    assert_eq!(totals(&["-b", "feature/**", "-b", "master"])?, 2);
+    // And tags are included on demand.
+    assert_eq!(totals(&["--tags"])?, 5);
+    assert_eq!(totals(&["--exclude-branch", "**", "--tags"])?, 2);
+
+    Ok(())
+}
+
+#[test]
+fn test_command_statistics_branch_type_all() -> Result<(), Box<dyn std::error::Error>> {
+    // Given we have a repository with a local branch.
+    let path_repository = tempdir().unwrap().path().join("");
+    let repository = Repository::init(&path_repository)?;
+    let initial =
+        create_commit_initial(&repository, String::from("Wed, 18 Feb 2015 10:00:00 GMT"))?;
+    let local = create_commit_detached(
+        &repository,
+        String::from("Wed, 18 Feb 2015 10:30:00 GMT"),
+        String::from("Local"),
+        &[initial],
+    )?;
+    repository.reference("refs/heads/feature", local, false, "test")?;
+    // And work that was only pushed to a colleague's remote branch.
+    let remote = create_commit_detached(
+        &repository,
+        String::from("Wed, 18 Feb 2015 11:00:00 GMT"),
+        String::from("Remote"),
+        &[initial],
+    )?;
+    repository.reference("refs/remotes/origin/colleague", remote, false, "test")?;
+
+    let totals = |args: &[&str]| -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+        let output = Command::cargo_bin(BIN)?
+            .arg("statistics")
+            .arg("--format")
+            .arg("json")
+            .args(args)
+            .arg(&path_repository)
+            .output()?;
+        assert!(output.status.success());
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+        Ok(report["totals"]["commit_count"].clone())
+    };
+
+    // When the user runs the command statistics on each type of branch.
+    // Then local and remote branches are analyzed separately.
+    assert_eq!(totals(&[])?, 2);
+    assert_eq!(totals(&["--branch-type", "remote"])?, 2);
+    // And both are analyzed at once, counting shared commits once.
+    assert_eq!(totals(&["--branch-type", "all"])?, 3);
+    assert_eq!(
+        totals(&["--branch-type", "all", "--branch", "**/colleague"])?,
+        2
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_command_statistics_pathspec() -> Result<(), Box<dyn std::error::Error>> {
+    // Given we have a repository with commits to several subsystems.
+    let path_repository = tempdir().unwrap().path().join("");
+    let repository = Repository::init(&path_repository)?;
+    create_commit_initial(&repository, String::from("Wed, 18 Feb 2015 10:00:00 GMT"))?;
+    for (path, time) in [
+        ("src/backend/server.rs", "Wed, 18 Feb 2015 10:30:00 GMT"),
+        ("docs/guide.md", "Wed, 18 Feb 2015 11:00:00 GMT"),
+        ("src/frontend/app.rs", "Wed, 18 Feb 2015 11:30:00 GMT"),
+    ] {
+        let path_file = path_repository.join(path);
+        std::fs::create_dir_all(path_file.parent().unwrap())?;
+        std::fs::write(&path_file, path)?;
+        create_commit(&repository, String::from(time), format!("Change {}", path))?;
+    }
+
+    let totals = |pathspecs: &[&str]| -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+        let output = Command::cargo_bin(BIN)?
+            .arg("statistics")
+            .arg("--format")
+            .arg("json")
+            .arg(&path_repository)
+            .arg("--")
+            .args(pathspecs)
+            .output()?;
+        assert!(output.status.success());
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+        Ok(report["totals"]["commit_count"].clone())
+    };
+
+    // When the user runs the command statistics on paths.
+    // Then only the commits touching the paths were counted.
+    assert_eq!(totals(&[])?, 4);
+    assert_eq!(totals(&["src/backend"])?, 1);
+    assert_eq!(totals(&["src/backend", "src/frontend"])?, 2);
+    assert_eq!(totals(&["*.md"])?, 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_command_statistics_by_component() -> Result<(), Box<dyn std::error::Error>> {
+    // Given the user has no configuration file.
+    let path_config_home = tempdir()?;
+
+    // And we have a repository with commits to several subsystems.
+    let path_repository = tempdir().unwrap().path().join("");
+    let repository = Repository::init(&path_repository)?;
+    create_commit_initial(&repository, String::from("Wed, 18 Feb 2015 10:00:00 GMT"))?;
+    for (path, time) in [
+        ("src/backend/server.rs", "Wed, 18 Feb 2015 10:30:00 GMT"),
+        ("docs/guide.md", "Wed, 18 Feb 2015 11:00:00 GMT"),
+        ("src/frontend/app.rs", "Wed, 18 Feb 2015 11:30:00 GMT"),
+    ] {
+        let path_file = path_repository.join(path);
+        std::fs::create_dir_all(path_file.parent().unwrap())?;
+        std::fs::write(&path_file, path)?;
+        create_commit(&repository, String::from(time), format!("Change {}", path))?;
+    }
+
+    let report = |args: &[&str]| -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+        let output = Command::cargo_bin(BIN)?
+            .env("XDG_CONFIG_HOME", path_config_home.path())
+            .arg("statistics")
+            .arg("--by-component")
+            .arg("--format")
+            .arg("json")
+            .args(args)
+            .arg(&path_repository)
+            .output()?;
+        assert!(output.status.success());
+        Ok(serde_json::from_slice(&output.stdout)?)
+    };
+
+    // When the user runs the command statistics by component.
+    let report_directories = report(&[])?;
+
+    // Then the time was split across the top-level directories.
+    assert_eq!(
+        report_directories["authors"][0]["components"],
+        serde_json::json!({ "(none)": 0.5, "docs": 0.5, "src": 1.0 })
+    );
+    assert_eq!(
+        report_directories["totals"]["components"],
+        serde_json::json!({ "(none)": 0.5, "docs": 0.5, "src": 1.0 })
+    );
+
+    // When the user runs the command statistics by component two directories deep.
+    let report_depth = report(&["--component-depth", "2"])?;
+
+    // Then the time was split across the subdirectories.
+    assert_eq!(
+        report_depth["totals"]["components"],
+        serde_json::json!({
+            "(none)": 0.5,
+            "docs": 0.5,
+            "src/backend": 0.5,
+            "src/frontend": 0.5
+        })
+    );
+
+    // When the repository names its components.
+    std::fs::write(
+        path_repository.join(".jikyuu.toml"),
+        "[components]\nbackend = [\"src/backend\", \"migrations\"]\ndocs = \"docs/*.md\"\n",
+    )?;
+    let report_named = report(&[])?;
+
+    // Then files outside the named components were counted as other.
+    assert_eq!(
+        report_named["totals"]["components"],
+        serde_json::json!({ "(none)": 0.5, "(other)": 0.5, "backend": 0.5, "docs": 0.5 })
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_command_statistics_author() -> Result<(), Box<dyn std::error::Error>> {
+    // Given we have a repository with commits by several authors.
+    let path_repository = tempdir().unwrap().path().join("");
+    let repository = Repository::init(&path_repository)?;
+    create_commit_initial(&repository, String::from("Wed, 18 Feb 2015 10:00:00 GMT"))?;
+    for (name, email, time) in [
+        (
+            "Nate",
+            "nate@old.example.com",
+            "Wed, 18 Feb 2015 10:30:00 GMT",
+        ),
+        ("Ruin", "ruin@example.com", "Wed, 18 Feb 2015 11:00:00 GMT"),
+        ("Bob", "bob@contractor.io", "Wed, 18 Feb 2015 11:30:00 GMT"),
+        (
+            "Release",
+            "release@example.com",
+            "Wed, 18 Feb 2015 12:00:00 GMT",
+        ),
+    ] {
+        create_commit_by(
+            &repository,
+            String::from(time),
+            format!("Commit by {}", name),
+            name,
+            email,
+        )?;
+    }
+
+    let emails = |args: &[&str]| -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let output = Command::cargo_bin(BIN)?
+            .arg("statistics")
+            .arg("--format")
+            .arg("json")
+            .arg("--email")
+            .arg("nate@old.example.com=nate-wilkins@code-null.com")
+            .args(args)
+            .arg(&path_repository)
+            .output()?;
+        assert!(output.status.success());
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+        let mut emails = report["authors"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|author| author["email"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>();
+        emails.sort();
+        Ok(emails)
+    };
+
+    // When the user runs the command statistics for authors.
+    // Then only the matching authors were reported, after their aliases were resolved.
+    assert_eq!(
+        emails(&["--author", "nate-wilkins@code-null.com", "--author", "Ruin"])?,
+        ["nate-wilkins@code-null.com", "ruin@example.com"]
+    );
+    assert_eq!(
+        emails(&["--author", ".*@example\\.com"])?,
+        ["release@example.com", "ruin@example.com"]
+    );
+
+    // When the user runs the command statistics excluding authors.
+    // Then the matching authors were left out.
+    assert_eq!(
+        emails(&[
+            "--exclude-author",
+            ".*@contractor\\.io",
+            "--exclude-author",
+            "release"
+        ])?,
+        ["nate-wilkins@code-null.com", "ruin@example.com"]
+    );
+
+    // When the user runs the command statistics with an invalid author regex.
+    let result = Command::cargo_bin(BIN)?
+        .arg("statistics")
+        .arg("--author")
+        .arg("(unclosed")
+        .arg(&path_repository)
+        .assert();
+
+    // Then the command failed.
+    result.failure().stderr(predicate::str::contains(
+        "Invalid author pattern '(unclosed'",
+    ));
+
+    // When the user runs the command statistics for an author without commits.
+    let result = Command::cargo_bin(BIN)?
+        .arg("statistics")
+        .arg("--author")
+        .arg("zzz")
+        .arg(&path_repository)
+        .assert();
+
+    // Then the command failed naming the filters instead of the commits.
+    result.failure().stderr(predicate::str::contains(
+        "No authors matched the filters: author 'zzz'.",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_command_statistics_bots() -> Result<(), Box<dyn std::error::Error>> {
+    // Given the user has no configuration file.
+    let path_config_home = tempdir()?;
+
+    // And we have a repository with commits by an author and by bots.
+    let path_repository = tempdir().unwrap().path().join("");
+    let repository = Repository::init(&path_repository)?;
+    create_commit_initial(&repository, String::from("Wed, 18 Feb 2015 10:00:00 GMT"))?;
+    for (name, email, time) in [
+        (
+            "dependabot[bot]",
+            "49699333+dependabot[bot]@users.noreply.github.com",
+            "Wed, 18 Feb 2015 10:30:00 GMT",
+        ),
+        (
+            "dependabot[bot]",
+            "49699333+dependabot[bot]@users.noreply.github.com",
+            "Wed, 18 Feb 2015 11:00:00 GMT",
+        ),
+        (
+            "github-actions",
+            "41898282+github-actions[bot]@users.noreply.github.com",
+            "Wed, 18 Feb 2015 11:30:00 GMT",
+        ),
+    ] {
+        create_commit_by(
+            &repository,
+            String::from(time),
+            format!("Commit by {}", name),
+            name,
+            email,
+        )?;
+    }
+
+    let report = |args: &[&str]| -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+        let output = Command::cargo_bin(BIN)?
+            .env("XDG_CONFIG_HOME", path_config_home.path())
+            .arg("statistics")
+            .arg("--format")
+            .arg("json")
+            .args(args)
+            .arg(&path_repository)
+            .output()?;
+        assert!(output.status.success());
+        Ok(serde_json::from_slice(&output.stdout)?)
+    };
+
+    // When the user runs the command statistics.
+    let report_default = report(&[])?;
+
+    // Then the bots were left out of the estimate.
+    assert_eq!(report_default["authors"].as_array().unwrap().len(), 1);
+    assert_eq!(report_default["totals"]["commit_count"], 1);
+
+    // Then the report lists the bots and their commits.
+    let mut bots = report_default["excluded_bots"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|bot| {
+            (
+                bot["author_name"].as_str().unwrap().to_string(),
+                bot["commit_count"].as_u64().unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+    bots.sort();
+    assert_eq!(
+        bots,
+        [
+            (String::from("dependabot[bot]"), 2),
+            (String::from("github-actions"), 1)
+        ]
+    );
+
+    // When the user runs the command statistics including bots.
+    let report_bots = report(&["--include-bots"])?;
+
+    // Then the bots were estimated like any other author.
+    assert_eq!(report_bots["authors"].as_array().unwrap().len(), 3);
+    assert_eq!(report_bots["totals"]["commit_count"], 4);
+    assert_eq!(report_bots["excluded_bots"], serde_json::json!([]));
+
+    // When the repository configures its own bots.
+    std::fs::write(
+        path_repository.join(".jikyuu.toml"),
+        "bots = \"github-.*\"\n",
+    )?;
+    let report_configured = report(&[])?;
+
+    // Then only the configured bots were left out.
+    assert_eq!(report_configured["totals"]["commit_count"], 3);
+
+    // When the user runs the command statistics as a table.
+    let result = Command::cargo_bin(BIN)?
+        .env("XDG_CONFIG_HOME", path_config_home.path())
+        .arg("statistics")
+        .arg(&path_repository)
+        .assert();
+
+    // Then the bots were listed after the estimate.
+    result.success().stdout(predicate::str::contains(
+        "
+Excluded bots:
++----------------+-------------------------------------------------------+---------+
+| Author         | Email                                                 | Commits |
+"
+        .trim_start(),
+    ));
+
+    // When the user runs the command statistics as HTML.
+    let result = Command::cargo_bin(BIN)?
+        .env("XDG_CONFIG_HOME", path_config_home.path())
+        .arg("statistics")
+        .arg("--format")
+        .arg("html")
+        .arg(&path_repository)
+        .assert();
+
+    // Then the page lists the bots in a table of their own, sorted by the columns of that table.
+    result
+        .success()
+        .stdout(predicate::str::contains("var column = th.cellIndex;"))
+        .stdout(predicate::str::contains(
+        "</table>
+<h2>Excluded bots</h2>
+<table>
+<thead>
+<tr><th>Author</th><th>Email</th><th>Commits</th></tr>
+</thead>
+<tbody>
+<tr><td>github-actions</td><td>41898282+github-actions[bot]@users.noreply.github.com</td><td>1</td></tr>
+</tbody>",
+    ));
+
+    // When the user runs the command statistics as CSV.
+    let result = Command::cargo_bin(BIN)?
+        .env("XDG_CONFIG_HOME", path_config_home.path())
+        .arg("statistics")
+        .arg("--format")
+        .arg("csv")
+        .arg(&path_repository)
+        .assert();
+
+    // Then the bots were logged instead of breaking the single table.
+    result
+        .success()
+        .stdout(predicate::str::contains("github-actions").not())
+        .stderr(predicate::str::contains(
+            "Excluded bot: github-actions <41898282+github-actions[bot]@users.noreply.github.com>, commits: 1",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn test_command_statistics_estimator() -> Result<(), Box<dyn std::error::Error>> {
+    // Given we have a repository with one session.
+    let path_repository = tempdir().unwrap().path().join("");
+    let repository = Repository::init(&path_repository)?;
+    create_commit_initial(&repository, String::from("Wed, 18 Feb 2015 10:00:00 GMT"))?;
+    create_commit(
+        &repository,
+        String::from("Wed, 18 Feb 2015 10:30:00 GMT"),
+        String::from("Commit A"),
+    )?;
+
+    let report = |args: &[&str]| -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+        let output = Command::cargo_bin(BIN)?
+            .arg("statistics")
+            .arg("--format")
+            .arg("json")
+            .args(args)
+            .arg(&path_repository)
+            .output()?;
+        assert!(output.status.success());
+        Ok(serde_json::from_slice(&output.stdout)?)
+    };
+
+    // When the user runs the command statistics with the git-hours estimator.
+    let report_git_hours = report(&["--estimator", "git-hours"])?;
+
+    // Then the estimate is the default estimate.
+    assert_eq!(report_git_hours["configuration"]["estimator"], "git-hours");
+    assert_eq!(report_git_hours["totals"], report(&[])?["totals"]);
+    assert_eq!(report_git_hours["totals"]["hours"], 1.0);
+
+    // When the user runs the command statistics with an unknown estimator.
+    let result = Command::cargo_bin(BIN)?
+        .arg("statistics")
+        .arg("--estimator")
+        .arg("astrology")
+        .arg(&path_repository)
+        .assert();
+
+    // Then the command failed.
+    result.failure();
+
+    Ok(())
+}
+
+#[test]
+fn test_command_statistics_estimator_diff_weighted() -> Result<(), Box<dyn std::error::Error>> {
+    // Given we have a repository with a one line fix and large changes.
+    let path_repository = tempdir().unwrap().path().join("");
+    let repository = Repository::init(&path_repository)?;
+    create_commit_initial(&repository, String::from("Wed, 18 Feb 2015 10:00:00 GMT"))?;
+    for (path, lines, time) in [
+        ("fix.txt", 1, "Wed, 18 Feb 2015 10:30:00 GMT"),
+        ("feature.txt", 400, "Wed, 18 Feb 2015 11:30:00 GMT"),
+        ("other.txt", 400, "Wed, 18 Feb 2015 18:00:00 GMT"),
+    ] {
+        std::fs::write(path_repository.join(path), "line\n".repeat(lines))?;
+        create_commit(&repository, String::from(time), format!("Change {}", path))?;
+    }
+
+    let report = |args: &[&str]| -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+        let output = Command::cargo_bin(BIN)?
+            .arg("statistics")
+            .arg("--format")
+            .arg("json")
+            .args(args)
+            .arg(&path_repository)
+            .output()?;
+        assert!(output.status.success());
+        Ok(serde_json::from_slice(&output.stdout)?)
+    };
+
+    // When the user runs the command statistics with the git-hours estimator.
+    // Then every commit counts the same regardless of its size.
+    assert_eq!(report(&[])?["totals"]["hours"], 2.5);
+
+    // When the user runs the command statistics with the diff-weighted estimator.
+    let report_weighted = report(&["--estimator", "diff-weighted"])?;
+
+    // Then the empty commit counted as one line, the one line fix was capped at 5 minutes and the large
+    // change added twice the first commit addition.
+    assert_eq!(
+        report_weighted["configuration"]["estimator"],
+        "diff-weighted"
+    );
+    assert_eq!(report_weighted["totals"]["hours"], 2.1333334);
+
+    // When the user tunes the coefficients of the diff-weighted estimator.
+    // Then the estimate follows the coefficients.
+    assert_eq!(
+        report(&[
+            "--estimator",
+            "diff-weighted",
+            "--diff-minutes-per-line",
+            "60"
+        ])?["totals"]["hours"],
+        2.55
+    );
+    assert_eq!(
+        report(&[
+            "--estimator",
+            "diff-weighted",
+            "--diff-reference-lines",
+            "400"
+        ])?["totals"]["hours"],
+        1.6
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_command_statistics_estimator_diff_weighted_empty_commit(
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Given the CLI.
+    let mut cmd = Command::cargo_bin(BIN)?;
+
+    // And we have a repository with only empty commits.
+    let path_repository = tempdir().unwrap().path().join("");
+    let repository = Repository::init(&path_repository)?;
+    create_commit_initial(&repository, String::from("Wed, 18 Feb 2015 10:00:00 GMT"))?;
+    create_commit(
+        &repository,
+        String::from("Wed, 18 Feb 2015 10:30:00 GMT"),
+        String::from("Empty"),
+    )?;
+
+    // When the user runs the command statistics with the diff-weighted estimator.
+    let output = cmd
+        .arg("statistics")
+        .arg("--estimator")
+        .arg("diff-weighted")
+        .arg("--format")
+        .arg("json")
+        .arg(&path_repository)
+        .output()?;
+
+    // Then no errors occurred.
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+
+    // Then the empty commits were estimated like one line changes instead of no time at all.
+    assert_eq!(report["totals"]["commit_count"], 2);
+    assert_eq!(report["totals"]["hours"], 0.13333334);
+
+    Ok(())
+}
+
+#[test]
+fn test_command_statistics_estimator_diff_weighted_group_by(
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Given the CLI.
+    let mut cmd = Command::cargo_bin(BIN)?;
+
+    // And we have a repository with one line fixes in a session spanning midnight.
+    let path_repository = tempdir().unwrap().path().join("");
+    let repository = Repository::init(&path_repository)?;
+    create_commit_initial(&repository, String::from("Wed, 18 Feb 2015 10:00:00 GMT"))?;
+    for (path, time) in [
+        ("a.txt", "Wed, 18 Feb 2015 23:00:00 GMT"),
+        ("b.txt", "Thu, 19 Feb 2015 00:30:00 GMT"),
+        ("c.txt", "Thu, 19 Feb 2015 01:00:00 GMT"),
+    ] {
+        std::fs::write(path_repository.join(path), "line\n")?;
+        create_commit(&repository, String::from(time), format!("Fix {}", path))?;
+    }
+
+    // When the user runs the command statistics grouped by day with the diff-weighted estimator.
+    let output = cmd
+        .arg("statistics")
+        .arg("--estimator")
+        .arg("diff-weighted")
+        .arg("--group-by")
+        .arg("day")
+        .arg("--time-zone")
+        .arg("utc")
+        .arg("--format")
+        .arg("json")
+        .arg(path_repository)
+        .output()?;
+
+    // Then no errors occurred.
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+
+    // Then the empty initial commit counted as one line and the capped time of each fix was
+    // counted on the day of the fix.
+    assert_eq!(
+        report["totals"]["periods"],
+        serde_json::json!({ "2015-02-18": 0.1, "2015-02-19": 0.16666667 })
+    );
+    assert_eq!(report["totals"]["hours"], 0.26666668);
+
+    Ok(())
+}